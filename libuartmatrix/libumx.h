@@ -7,6 +7,62 @@
 
 #define MAX_TEXT_LENGTH 255
 
+#define PROTOCOL_VERSION 2
+
+#define FRAME_OVERHEAD 6
+
+#define MAX_PAYLOAD_SIZE (MAX_FRAME_SIZE - FRAME_OVERHEAD)
+
+#define MAX_ROW_PIXELS ((MAX_PAYLOAD_SIZE - 2) / 3)
+
+#define UMX_ERR_TEXT_TOO_LONG -1
+
+#define UMX_ERR_FRAME_TOO_LARGE -2
+
+#define UMX_ERR_INVALID_FONT -3
+
+#define UMX_ERR_INVALID_UTF8 -4
+
+#define UMX_ERR_UNKNOWN_OPCODE -5
+
+#define UMX_ERR_UNKNOWN_RESPONSE -6
+
+#define UMX_ERR_MALFORMED_PAYLOAD -7
+
+#define UMX_ERR_NULL_POINTER -8
+
+#define UMX_ERR_BUFFER_TOO_SMALL -9
+
+#define UMX_ERR_CRC_MISMATCH -10
+
+enum Opcode {
+  Opcode_ParamRequest = 0,
+  Opcode_SwitchMode = 1,
+  Opcode_WriteLine = 2,
+  Opcode_SetFont = 3,
+  Opcode_SetColor = 4,
+  Opcode_SetAnimation = 5,
+  Opcode_DrawPixel = 6,
+  Opcode_DrawRow = 7,
+  Opcode_DrawLine = 8,
+  Opcode_DrawRectangle = 9,
+  Opcode_DrawTriangle = 10,
+  Opcode_DrawCircle = 11,
+  Opcode_Clear = 12,
+  Opcode_EnableOutput = 13,
+  Opcode_DisableOutput = 14,
+  Opcode_Ping = 15,
+};
+typedef uint8_t Opcode;
+
+enum ResponseCode {
+  ResponseCode_Ack = 128,
+  ResponseCode_Nack = 129,
+  ResponseCode_Pong = 130,
+  ResponseCode_ParamReport = 131,
+};
+typedef uint8_t ResponseCode;
+
 typedef struct RgbColor {
   uint8_t r;
   uint8_t g;
@@ -0,0 +1,830 @@
+//! Async counterpart of [`MatrixClient`](crate::MatrixClient) for tokio.
+//!
+//! [`AsyncMatrixClient`] runs the link in a background task that works over any async
+//! byte stream, e.g. a `tokio::net::TcpStream` or a serial port opened with
+//! `open_serial` (`tokio-serial` feature). Commands are pipelined through a sliding
+//! window: up to `window` frames wait for their reply at the same time, and each reply
+//! makes room for the next queued frame.
+//!
+//! Replies carry no sequence number, only the opcode of the frame they answer. The
+//! device answers frames in the order it got them, so each reply is credited to the
+//! oldest frame still waiting, and frames with the same opcode are never in flight at the
+//! same time. A reply for a later frame thus shows that the oldest one was lost.
+//!
+//! A lost frame, a missing reply or a NACK for a corrupted frame has every frame in
+//! flight sent again, once the device went quiet. The frames after the lost one are then
+//! applied twice, which is harmless since every command sets state rather than changing
+//! it. Like the blocking client, the link falls back to timed pacing if the first few
+//! commands all go unanswered.
+
+use std::collections::VecDeque;
+use std::io;
+use std::sync::Arc;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time::{self, Instant};
+
+use crate::client::{answers, is_request, is_transient, SILENT_COMMANDS_BEFORE_TIMED};
+use crate::enums::{Animation, DisplayMode, FontType};
+use crate::{
+    serialize_clear, serialize_command, serialize_draw_circle, serialize_draw_line,
+    serialize_draw_pixel, serialize_draw_rectangle, serialize_draw_row, serialize_draw_triangle,
+    serialize_param_request, serialize_ping, serialize_set_animation, serialize_set_color,
+    serialize_set_font, serialize_switch_mode, serialize_write_line, AckMode, ClientError, Command,
+    DeviceParams, Frame, FrameDecoder, NackReason, Pacing, Point, Response, RgbColor, FRAME_HEADER,
+};
+
+type Reply = oneshot::Sender<Result<Option<Response>, ClientError>>;
+
+struct Request {
+    frame: Frame,
+    reply: Reply,
+}
+
+/// Handle to a panel driven by a background task, cheap to clone and share between tasks.
+#[derive(Clone)]
+pub struct AsyncMatrixClient {
+    requests: mpsc::Sender<Request>,
+    task: Arc<JoinHandle<()>>,
+}
+
+impl AsyncMatrixClient {
+    /// Starts the link task on the current tokio runtime.
+    ///
+    /// `window` is the number of frames that may wait for their acknowledgement at the
+    /// same time, 1 sends every frame only after the previous one was answered. Until the
+    /// device acknowledged a frame, only one is in flight, and frames with the same
+    /// opcode always wait for each other.
+    pub fn new<T>(io: T, pacing: Pacing, window: usize) -> Self
+    where
+        T: AsyncRead + AsyncWrite + Send + 'static,
+    {
+        let window = window.max(1);
+        let (requests, receiver) = mpsc::channel(window);
+        let task = tokio::spawn(run(io, receiver, pacing, window));
+
+        AsyncMatrixClient {
+            requests,
+            task: Arc::new(task),
+        }
+    }
+
+    /// Stops the link task; pending and later requests fail with `Cancelled`.
+    pub fn shutdown(&self) {
+        self.task.abort();
+    }
+
+    /// Sends any command and waits for the device's reply, `None` if it does not
+    /// acknowledge.
+    ///
+    /// Dropping the returned future cancels the command if it was not written yet.
+    pub async fn send(&self, command: &Command) -> Result<Option<Response>, ClientError> {
+        self.send_frame(serialize_command(command)?).await
+    }
+
+    /// Like `send`, for a frame that was already serialized.
+    pub async fn send_frame(&self, frame: Frame) -> Result<Option<Response>, ClientError> {
+        let response = self.queue(frame).await?;
+        response.await.unwrap_or(Err(ClientError::Cancelled))
+    }
+
+    pub async fn ping(&self) -> Result<u8, ClientError> {
+        match self.send_frame(serialize_ping()?).await? {
+            Some(Response::Pong { version }) => Ok(version),
+            Some(response) => Err(ClientError::UnexpectedResponse(response)),
+            None => Err(ClientError::NoResponse),
+        }
+    }
+
+    pub async fn params(&self) -> Result<DeviceParams, ClientError> {
+        match self.send_frame(serialize_param_request()?).await? {
+            Some(Response::ParamReport(params)) => Ok(params),
+            Some(response) => Err(ClientError::UnexpectedResponse(response)),
+            None => Err(ClientError::NoResponse),
+        }
+    }
+
+    pub async fn switch_mode(&self, mode: DisplayMode) -> Result<(), ClientError> {
+        self.deliver(serialize_switch_mode(mode)?).await
+    }
+
+    pub async fn write_line(&self, row: u8, text: &str) -> Result<(), ClientError> {
+        self.deliver(serialize_write_line(row, text)?).await
+    }
+
+    pub async fn set_font(&self, row: u8, font: FontType) -> Result<(), ClientError> {
+        self.deliver(serialize_set_font(row, font.into())?).await
+    }
+
+    pub async fn set_color(&self, row: u8, color: RgbColor) -> Result<(), ClientError> {
+        self.deliver(serialize_set_color(row, color)?).await
+    }
+
+    pub async fn set_animation(&self, row: u8, animation: Animation) -> Result<(), ClientError> {
+        self.deliver(serialize_set_animation(row, animation)?).await
+    }
+
+    pub async fn clear(&self) -> Result<(), ClientError> {
+        self.deliver(serialize_clear()?).await
+    }
+
+    pub async fn draw_pixel(&self, position: Point, color: RgbColor) -> Result<(), ClientError> {
+        self.deliver(serialize_draw_pixel(position, color)?).await
+    }
+
+    pub async fn draw_line(
+        &self,
+        point_1: Point,
+        point_2: Point,
+        thickness: u8,
+        color: RgbColor,
+    ) -> Result<(), ClientError> {
+        let frame = serialize_draw_line(point_1, point_2, thickness, color)?;
+        self.deliver(frame).await
+    }
+
+    pub async fn draw_rectangle(
+        &self,
+        point_1: Point,
+        point_2: Point,
+        thickness: u8,
+        color: RgbColor,
+        filled: bool,
+    ) -> Result<(), ClientError> {
+        let frame = serialize_draw_rectangle(point_1, point_2, thickness, color, filled)?;
+        self.deliver(frame).await
+    }
+
+    pub async fn draw_triangle(
+        &self,
+        point_1: Point,
+        point_2: Point,
+        point_3: Point,
+        thickness: u8,
+        color: RgbColor,
+        filled: bool,
+    ) -> Result<(), ClientError> {
+        let frame = serialize_draw_triangle(point_1, point_2, point_3, thickness, color, filled)?;
+        self.deliver(frame).await
+    }
+
+    pub async fn draw_circle(
+        &self,
+        center: Point,
+        radius: u8,
+        thickness: u8,
+        color: RgbColor,
+        filled: bool,
+    ) -> Result<(), ClientError> {
+        let frame = serialize_draw_circle(center, radius, thickness, color, filled)?;
+        self.deliver(frame).await
+    }
+
+    pub async fn draw_row<I>(&self, row: u8, pixels: I) -> Result<(), ClientError>
+    where
+        I: IntoIterator<Item = (u8, u8, u8)>,
+    {
+        self.deliver(serialize_draw_row(row, pixels)?).await
+    }
+
+    /// Draws an image row by row. The rows share an opcode, so each one is only sent once
+    /// the previous one was acknowledged.
+    ///
+    /// Nothing is sent if the image has more rows than a row index can address.
+    pub async fn upload_image<I, R>(&self, rows: I) -> Result<(), ClientError>
+    where
+        I: IntoIterator<Item = R>,
+        R: IntoIterator<Item = (u8, u8, u8)>,
+    {
+        let rows: Vec<R> = rows.into_iter().collect();
+        let max = u8::MAX as usize + 1;
+        if rows.len() > max {
+            return Err(ClientError::ImageTooTall {
+                height: rows.len(),
+                max,
+            });
+        }
+
+        let mut responses = vec![];
+        for (i, row) in rows.into_iter().enumerate() {
+            responses.push(self.queue(serialize_draw_row(i as u8, row)?).await?);
+        }
+
+        for response in responses {
+            response.await.unwrap_or(Err(ClientError::Cancelled))?;
+        }
+
+        Ok(())
+    }
+
+    async fn deliver(&self, frame: Frame) -> Result<(), ClientError> {
+        self.send_frame(frame).await.map(drop)
+    }
+
+    /// Hands `frame` to the link task, waiting while the queue is full.
+    async fn queue(
+        &self,
+        frame: Frame,
+    ) -> Result<oneshot::Receiver<Result<Option<Response>, ClientError>>, ClientError> {
+        let (reply, response) = oneshot::channel();
+        self.requests
+            .send(Request { frame, reply })
+            .await
+            .map_err(|_| ClientError::Cancelled)?;
+
+        Ok(response)
+    }
+}
+
+/// Opens a serial port for the async client.
+#[cfg(feature = "tokio-serial")]
+pub fn open_serial(path: &str, baud_rate: u32) -> tokio_serial::Result<tokio_serial::SerialStream> {
+    use tokio_serial::SerialPortBuilderExt;
+
+    tokio_serial::new(path, baud_rate).open_native_async()
+}
+
+/// A frame on the wire, waiting for its reply.
+struct InFlight {
+    frame: Frame,
+    opcode: u8,
+    reply: Reply,
+}
+
+/// The frames written so far that were not answered yet, oldest first.
+struct Pipeline<W> {
+    writer: W,
+    pacing: Pacing,
+    window: usize,
+    mode: AckMode,
+    //Commands in a row that went unanswered while the mode is unknown
+    silent: u8,
+    in_flight: VecDeque<InFlight>,
+    //The next request, held back until it may be written
+    waiting: Option<Request>,
+    //Retransmissions of the oldest frame
+    attempts: u8,
+    //Set by a NACK for a corrupted frame, reported if the retries run out
+    rejected: Option<NackReason>,
+    //When the oldest frame times out, or in timed mode when the next frame may be written
+    deadline: Option<Instant>,
+    //Replies are dropped until the deadline, then every frame in flight is sent again
+    recovering: bool,
+}
+
+impl<W: AsyncWrite + Unpin> Pipeline<W> {
+    fn is_idle(&self) -> bool {
+        self.in_flight.is_empty() && self.waiting.is_none()
+    }
+
+    /// Whether a frame with `opcode` may be written now.
+    fn can_send(&self, opcode: u8) -> bool {
+        //Firmware that turns out not to acknowledge must not have been flooded before
+        let window = match self.mode {
+            AckMode::Acknowledged => self.window,
+            _ => 1,
+        };
+        let paused = self.in_flight.is_empty() && self.deadline.is_some();
+        //Its reply could not be told from that of the frame in flight, if one was lost
+        let ambiguous = self.in_flight.iter().any(|frame| frame.opcode == opcode);
+
+        !self.recovering && !paused && !ambiguous && self.in_flight.len() < window
+    }
+
+    async fn send_waiting(&mut self) -> io::Result<()> {
+        match self.waiting.take() {
+            Some(request) if self.can_send(request.frame[FRAME_HEADER.len() + 2]) => {
+                self.send(request).await
+            }
+            waiting => {
+                self.waiting = waiting;
+                Ok(())
+            }
+        }
+    }
+
+    async fn send(&mut self, request: Request) -> io::Result<()> {
+        //Nobody waits for this anymore, the caller dropped its future
+        if request.reply.is_closed() {
+            return Ok(());
+        }
+
+        let opcode = request.frame[FRAME_HEADER.len() + 2];
+        self.writer.write_all(&request.frame).await?;
+
+        if self.mode == AckMode::Timed && !is_request(opcode) {
+            let _ = request.reply.send(Ok(None));
+            self.deadline = Some(Instant::now() + self.pacing.fallback_delay(opcode));
+            return Ok(());
+        }
+
+        if self.in_flight.is_empty() {
+            self.attempts = 0;
+            self.rejected = None;
+            self.deadline = Some(Instant::now() + self.pacing.ack_timeout);
+        }
+        self.in_flight.push_back(InFlight {
+            frame: request.frame,
+            opcode,
+            reply: request.reply,
+        });
+
+        Ok(())
+    }
+
+    /// Writes every frame in flight again, after one was lost or corrupted.
+    async fn transmit(&mut self) -> io::Result<()> {
+        self.in_flight.retain(|frame| !frame.reply.is_closed());
+        self.recovering = false;
+        if self.in_flight.is_empty() {
+            self.deadline = None;
+            return Ok(());
+        }
+
+        for frame in self.in_flight.iter() {
+            self.writer.write_all(&frame.frame).await?;
+        }
+        self.attempts += 1;
+        self.deadline = Some(Instant::now() + self.pacing.ack_timeout);
+
+        Ok(())
+    }
+
+    /// Credits `response` to the oldest frame in flight, the device answers in order.
+    fn handle(&mut self, response: Response) {
+        if self.recovering {
+            return;
+        }
+        let oldest = match self.in_flight.front() {
+            Some(frame) => frame.opcode,
+            None => return,
+        };
+
+        if !answers(oldest, &response) {
+            //A reply for a later frame means the oldest one was lost, anything else is a
+            //late reply to an earlier attempt
+            if self
+                .in_flight
+                .iter()
+                .skip(1)
+                .any(|frame| answers(frame.opcode, &response))
+            {
+                self.recover();
+            }
+            return;
+        }
+
+        if let Response::Ack { .. } | Response::Nack { .. } = response {
+            self.mode = AckMode::Acknowledged;
+        }
+        if let Response::Nack { reason, .. } = response {
+            if is_transient(reason) {
+                self.rejected = Some(reason);
+                self.recover();
+                return;
+            }
+        }
+
+        let result = match response {
+            Response::Nack { reason, .. } => Err(ClientError::Rejected(reason)),
+            response => Ok(Some(response)),
+        };
+        self.resolve(result);
+    }
+
+    /// Called once the deadline passed.
+    async fn expire(&mut self) -> io::Result<()> {
+        let oldest = match self.in_flight.front() {
+            Some(frame) => frame.opcode,
+            None => {
+                //The delay after a frame in timed mode is over
+                self.deadline = None;
+                return Ok(());
+            }
+        };
+
+        if !self.recovering {
+            //Silence from a device that never answered might mean it does not acknowledge
+            //at all, but one lost frame or ACK is not enough to tell
+            if self.mode == AckMode::Unknown && !is_request(oldest) {
+                self.silent += 1;
+                if self.silent >= SILENT_COMMANDS_BEFORE_TIMED {
+                    self.mode = AckMode::Timed;
+                }
+                self.resolve(Ok(None));
+                self.deadline = Some(Instant::now() + self.pacing.fallback_delay(oldest));
+            } else {
+                self.recover();
+            }
+            return Ok(());
+        }
+
+        if self.attempts >= self.pacing.retries {
+            let error = match self.rejected {
+                Some(reason) => ClientError::Rejected(reason),
+                None => ClientError::NoResponse,
+            };
+            self.resolve(Err(error));
+            //The frames after it were dropped along with it, so they are sent again
+            if self.in_flight.is_empty() {
+                return Ok(());
+            }
+        }
+
+        self.transmit().await
+    }
+
+    /// Answers the oldest frame, the next one gets a full timeout of its own.
+    fn resolve(&mut self, result: Result<Option<Response>, ClientError>) {
+        if let Some(frame) = self.in_flight.pop_front() {
+            let _ = frame.reply.send(result);
+        }
+
+        self.attempts = 0;
+        self.rejected = None;
+        self.recovering = false;
+        self.deadline = if self.in_flight.is_empty() {
+            None
+        } else {
+            Some(Instant::now() + self.pacing.ack_timeout)
+        };
+    }
+
+    fn recover(&mut self) {
+        //Replies to frames still on the wire would be taken for those of the next attempt
+        self.recovering = true;
+        self.deadline = Some(Instant::now() + self.pacing.ack_timeout);
+    }
+
+    fn fail(&mut self, error: &io::Error) {
+        let replies = self.in_flight.drain(..).map(|frame| frame.reply);
+        for reply in replies.chain(self.waiting.take().map(|request| request.reply)) {
+            let error = io::Error::new(error.kind(), error.to_string());
+            let _ = reply.send(Err(ClientError::Io(error)));
+        }
+    }
+}
+
+async fn run<T>(io: T, mut requests: mpsc::Receiver<Request>, pacing: Pacing, window: usize)
+where
+    T: AsyncRead + AsyncWrite,
+{
+    let (mut reader, writer) = tokio::io::split(io);
+    let mut pipeline = Pipeline {
+        writer,
+        pacing,
+        window,
+        mode: AckMode::Unknown,
+        silent: 0,
+        in_flight: VecDeque::with_capacity(window),
+        waiting: None,
+        attempts: 0,
+        rejected: None,
+        deadline: None,
+        recovering: false,
+    };
+    let mut decoder = FrameDecoder::new();
+    let mut buffer = [0; 64];
+    let mut open = true;
+
+    let result = loop {
+        if let Err(e) = pipeline.send_waiting().await {
+            break Err(e);
+        }
+        if !open && pipeline.is_idle() {
+            break Ok(());
+        }
+
+        let deadline = pipeline.deadline;
+
+        tokio::select! {
+            request = requests.recv(), if open && pipeline.waiting.is_none() => match request {
+                Some(request) => pipeline.waiting = Some(request),
+                None => open = false,
+            },
+            read = reader.read(&mut buffer) => {
+                let count = match read {
+                    Ok(0) => break Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "connection closed by the device",
+                    )),
+                    Ok(count) => count,
+                    Err(e) => break Err(e),
+                };
+
+                let mut responses = vec![];
+                decoder.feed(&buffer[..count], |payload| {
+                    if let Ok(response) = Response::decode(payload) {
+                        responses.push(response);
+                    }
+                });
+                for response in responses {
+                    pipeline.handle(response);
+                }
+            },
+            _ = time::sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                decoder.reset();
+                if let Err(e) = pipeline.expire().await {
+                    break Err(e);
+                }
+            },
+        }
+    };
+
+    if let Err(e) = result {
+        pipeline.fail(&e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::io::DuplexStream;
+
+    use super::*;
+    use crate::enums::Opcode;
+    use crate::{serialize_response, UmxError};
+
+    fn pacing() -> Pacing {
+        Pacing {
+            ack_timeout: Duration::from_millis(20),
+            retries: 3,
+            ..Pacing::default()
+        }
+    }
+
+    /// Acknowledges every frame, except that `drop` picks frames to ignore or corrupt
+    /// by their position in the received order. Returns the accepted payloads.
+    async fn device<F>(mut io: DuplexStream, mut drop: F) -> Vec<Vec<u8>>
+    where
+        F: FnMut(usize, &[u8]) -> Option<Option<NackReason>>,
+    {
+        let mut decoder = FrameDecoder::new();
+        let mut buffer = [0; 256];
+        let mut received = 0;
+        let mut accepted = vec![];
+
+        while let Ok(count) = io.read(&mut buffer).await {
+            if count == 0 {
+                break;
+            }
+
+            let mut payloads = vec![];
+            decoder.feed(&buffer[..count], |payload| payloads.push(payload.to_vec()));
+            for payload in payloads {
+                let opcode = payload[0];
+                let response = match drop(received, &payload) {
+                    Some(None) => None,
+                    Some(Some(reason)) => Some(Response::Nack { opcode, reason }),
+                    None if opcode == Opcode::Ping.into() => Some(Response::Pong { version: 2 }),
+                    None => {
+                        accepted.push(payload.clone());
+                        Some(Response::Ack { opcode })
+                    }
+                };
+                received += 1;
+
+                if let Some(response) = response {
+                    let frame = serialize_response(&response).unwrap();
+                    io.write_all(&frame).await.unwrap();
+                }
+            }
+        }
+
+        accepted
+    }
+
+    fn rows(count: u8) -> Vec<Vec<(u8, u8, u8)>> {
+        (0..count).map(|i| vec![(i, i, i); 4]).collect()
+    }
+
+    /// The payload of `frame`, as the device sees it.
+    fn payload(frame: Result<Frame, UmxError>) -> Vec<u8> {
+        let frame = frame.unwrap();
+        frame[FRAME_HEADER.len() + 2..frame.len() - 1].to_vec()
+    }
+
+    fn draw_rows(count: u8) -> Vec<Vec<u8>> {
+        rows(count)
+            .into_iter()
+            .enumerate()
+            .map(|(i, row)| payload(serialize_draw_row(i as u8, row)))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn pipelines_commands() {
+        let (host, device_io) = tokio::io::duplex(4096);
+        let device = tokio::spawn(device(device_io, |_, _| None));
+        let client = AsyncMatrixClient::new(host, pacing(), 4);
+
+        assert_eq!(client.ping().await.unwrap(), 2);
+        client.upload_image(rows(8)).await.unwrap();
+        drop(client);
+
+        assert_eq!(device.await.unwrap(), draw_rows(8));
+    }
+
+    #[tokio::test]
+    async fn resends_lost_and_corrupted_rows() {
+        let (host, device_io) = tokio::io::duplex(4096);
+        let mut copies = [0; 6];
+        let device = tokio::spawn(device(device_io, move |_, payload| {
+            let row = payload[1] as usize;
+            copies[row] += 1;
+            match (row, copies[row]) {
+                (2, 1) => Some(None),
+                (3, 1) => Some(Some(NackReason::BadCrc)),
+                (5, 1) => Some(None),
+                _ => None,
+            }
+        }));
+        let client = AsyncMatrixClient::new(host, pacing(), 3);
+
+        client.upload_image(rows(6)).await.unwrap();
+        drop(client);
+
+        //The first copies of rows 2 and 5 were never applied, so they must have been resent
+        assert_eq!(device.await.unwrap(), draw_rows(6));
+    }
+
+    #[tokio::test]
+    async fn resends_frames_after_a_lost_one() {
+        let (host, device_io) = tokio::io::duplex(4096);
+        let mut lost = false;
+        let device = tokio::spawn(device(device_io, move |_, payload| {
+            if payload[0] == Opcode::SetColor.into() && !lost {
+                lost = true;
+                Some(None)
+            } else {
+                None
+            }
+        }));
+        let client = AsyncMatrixClient::new(host, pacing(), 4);
+        client.clear().await.unwrap();
+
+        let color = RgbColor::new();
+        let position = Point { x: 1, y: 2 };
+        let results = tokio::join!(
+            client.set_color(0, color),
+            client.draw_pixel(position, color),
+            client.write_line(0, "HI"),
+        );
+        assert!(results.0.is_ok() && results.1.is_ok() && results.2.is_ok());
+        drop(client);
+
+        //The ACK for the pixel shows that the color was lost, so everything after it is
+        //sent again
+        let accepted = device.await.unwrap();
+        assert_eq!(
+            accepted[accepted.len() - 3..],
+            [
+                payload(serialize_set_color(0, color)),
+                payload(serialize_draw_pixel(position, color)),
+                payload(serialize_write_line(0, "HI")),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn keeps_acknowledging_after_a_lost_first_ack() {
+        let (host, device_io) = tokio::io::duplex(4096);
+        let device = tokio::spawn(device(device_io, |received, _| match received {
+            0 | 2 => Some(None),
+            _ => None,
+        }));
+        let client = AsyncMatrixClient::new(host, pacing(), 2);
+        let color = RgbColor::new();
+        let position = Point { x: 1, y: 2 };
+
+        client.clear().await.unwrap();
+        client.set_color(0, color).await.unwrap();
+        //Lost like the first frame, but now it is sent again
+        client.draw_pixel(position, color).await.unwrap();
+        drop(client);
+
+        assert_eq!(
+            device.await.unwrap(),
+            [
+                payload(serialize_set_color(0, color)),
+                payload(serialize_draw_pixel(position, color)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn credits_replies_to_the_oldest_frame() {
+        let (host, device_io) = tokio::io::duplex(4096);
+        tokio::spawn(device(device_io, |_, payload| {
+            //Only row 2 is rejected, the other rows get the same ACK
+            if payload[0] == Opcode::DrawRow.into() && payload[1] == 2 {
+                Some(Some(NackReason::BadRow))
+            } else {
+                None
+            }
+        }));
+        let client = AsyncMatrixClient::new(host, pacing(), 4);
+        client.clear().await.unwrap();
+
+        let rows = rows(4);
+        let results = tokio::join!(
+            client.draw_row(0, rows[0].clone()),
+            client.draw_row(1, rows[1].clone()),
+            client.draw_row(2, rows[2].clone()),
+            client.draw_row(3, rows[3].clone()),
+        );
+
+        assert!(results.0.is_ok());
+        assert!(results.1.is_ok());
+        assert!(matches!(
+            results.2,
+            Err(ClientError::Rejected(NackReason::BadRow))
+        ));
+        assert!(results.3.is_ok());
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_timed_pacing_for_silent_firmware() {
+        let (host, mut device_io) = tokio::io::duplex(4096);
+        let device = tokio::spawn(async move {
+            let mut received = vec![];
+            device_io.read_to_end(&mut received).await.unwrap();
+            received
+        });
+        let client = AsyncMatrixClient::new(host, pacing(), 4);
+
+        assert_eq!(client.send(&Command::Clear).await.unwrap(), None);
+        client.upload_image(rows(3)).await.unwrap();
+        assert!(matches!(client.ping().await, Err(ClientError::NoResponse)));
+        drop(client);
+
+        //Nothing but the ping is sent twice
+        let mut expected = serialize_clear().unwrap().to_vec();
+        for i in 0..3 {
+            expected
+                .extend_from_slice(&serialize_draw_row(i, rows(3)[i as usize].clone()).unwrap());
+        }
+        for _ in 0..=pacing().retries {
+            expected.extend_from_slice(&serialize_ping().unwrap());
+        }
+        assert_eq!(device.await.unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn rejects_images_taller_than_the_row_index() {
+        let (host, _device_io) = tokio::io::duplex(4096);
+        let client = AsyncMatrixClient::new(host, pacing(), 4);
+
+        assert!(matches!(
+            client.upload_image(vec![vec![(0, 0, 0); 1]; 257]).await,
+            Err(ClientError::ImageTooTall {
+                height: 257,
+                max: 256
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn reports_rejections_and_silence() {
+        let (host, device_io) = tokio::io::duplex(4096);
+        tokio::spawn(device(device_io, |_, payload| {
+            if payload[0] == Opcode::WriteLine.into() {
+                Some(Some(NackReason::BadRow))
+            } else if payload[0] == Opcode::Clear.into() {
+                Some(None)
+            } else {
+                None
+            }
+        }));
+        let client = AsyncMatrixClient::new(host, pacing(), 2);
+
+        assert!(matches!(
+            client.write_line(9, "HI").await,
+            Err(ClientError::Rejected(NackReason::BadRow))
+        ));
+        assert!(matches!(client.clear().await, Err(ClientError::NoResponse)));
+        client.set_color(0, RgbColor::new()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn shutdown_cancels_requests() {
+        let (host, _device_io) = tokio::io::duplex(4096);
+        let client = AsyncMatrixClient::new(host, pacing(), 1);
+
+        let pending = tokio::spawn({
+            let client = client.clone();
+            async move { client.clear().await }
+        });
+        tokio::task::yield_now().await;
+        client.shutdown();
+
+        assert!(matches!(
+            pending.await.unwrap(),
+            Err(ClientError::Cancelled)
+        ));
+        assert!(matches!(client.clear().await, Err(ClientError::Cancelled)));
+    }
+}
@@ -0,0 +1,639 @@
+//! Blocking host-side API for driving a panel.
+//!
+//! [`MatrixClient`] frames commands, sends them through a [`Transport`] and waits for
+//! the device to acknowledge each one before the next is sent. Frames that were lost or
+//! corrupted on the way are retransmitted. Older firmware that never acknowledges is
+//! detected once the first few commands all went unanswered, and paced with fixed delays
+//! from then on.
+
+use std::convert::TryFrom;
+use std::time::{Duration, Instant};
+use std::{error, fmt, io, thread};
+
+use crate::enums::{Animation, DisplayMode, FontType, Opcode};
+use crate::transport::Transport;
+use crate::{
+    serialize_clear, serialize_command, serialize_disable_output, serialize_draw_circle,
+    serialize_draw_line, serialize_draw_pixel, serialize_draw_rectangle, serialize_draw_row,
+    serialize_draw_triangle, serialize_enable_output, serialize_param_request, serialize_ping,
+    serialize_set_animation, serialize_set_color, serialize_set_font, serialize_switch_mode,
+    serialize_write_line, Command, DeviceParams, Frame, FrameDecoder, NackReason, Point, Response,
+    RgbColor, UmxError, FRAME_HEADER,
+};
+
+/// Unanswered commands in a row, before any was acknowledged, that make a device count
+/// as one that does not acknowledge at all.
+pub(crate) const SILENT_COMMANDS_BEFORE_TIMED: u8 = 3;
+
+/// How long to wait for acknowledgements and how to space frames for firmware without them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pacing {
+    pub ack_timeout: Duration,
+    /// Retransmissions after a timeout or a NACK for a corrupted or dropped frame.
+    pub retries: u8,
+    /// Delay after settings and text commands when the device does not acknowledge.
+    pub command_delay: Duration,
+    /// Delay after draw commands when the device does not acknowledge.
+    pub draw_delay: Duration,
+}
+
+impl Default for Pacing {
+    fn default() -> Self {
+        Pacing {
+            ack_timeout: Duration::from_millis(100),
+            retries: 3,
+            command_delay: Duration::from_millis(20),
+            draw_delay: Duration::from_millis(50),
+        }
+    }
+}
+
+impl Pacing {
+    /// How long to wait after a frame with `opcode` when the device does not acknowledge.
+    pub(crate) fn fallback_delay(&self, opcode: u8) -> Duration {
+        match Opcode::try_from(opcode) {
+            Ok(Opcode::DrawPixel)
+            | Ok(Opcode::DrawRow)
+            | Ok(Opcode::DrawLine)
+            | Ok(Opcode::DrawRectangle)
+            | Ok(Opcode::DrawTriangle)
+            | Ok(Opcode::DrawCircle)
+            | Ok(Opcode::Clear) => self.draw_delay,
+            _ => self.command_delay,
+        }
+    }
+}
+
+/// Whether the device acknowledges frames, learned from its replies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AckMode {
+    Unknown,
+    Acknowledged,
+    /// The first commands went unanswered, frames are spaced by the pacing delays.
+    Timed,
+}
+
+#[derive(Debug)]
+pub enum ClientError {
+    Encode(UmxError),
+    Io(io::Error),
+    Rejected(NackReason),
+    NoResponse,
+    UnexpectedResponse(Response),
+    /// The image has more rows than a row index can address.
+    ImageTooTall {
+        height: usize,
+        max: usize,
+    },
+    /// The async client stopped before the request was answered.
+    Cancelled,
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Encode(e) => write!(f, "{}", e),
+            ClientError::Io(e) => write!(f, "sending failed: {}", e),
+            ClientError::Rejected(reason) => write!(f, "rejected: {:?}", reason),
+            ClientError::NoResponse => write!(f, "no response from device"),
+            ClientError::UnexpectedResponse(response) => {
+                write!(f, "unexpected response {:?}", response)
+            }
+            ClientError::ImageTooTall { height, max } => {
+                write!(f, "image has {} rows, the limit is {}", height, max)
+            }
+            ClientError::Cancelled => write!(f, "request cancelled"),
+        }
+    }
+}
+
+impl error::Error for ClientError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            ClientError::Encode(e) => Some(e),
+            ClientError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<UmxError> for ClientError {
+    fn from(e: UmxError) -> Self {
+        ClientError::Encode(e)
+    }
+}
+
+impl From<io::Error> for ClientError {
+    fn from(e: io::Error) -> Self {
+        ClientError::Io(e)
+    }
+}
+
+/// Sends commands to a panel one at a time, each only after the previous one was answered.
+pub struct MatrixClient<T: Transport> {
+    transport: T,
+    pacing: Pacing,
+    mode: AckMode,
+    //Commands in a row that went unanswered while the mode is unknown
+    silent: u8,
+    decoder: FrameDecoder,
+}
+
+impl<T: Transport> MatrixClient<T> {
+    pub fn new(transport: T) -> Self {
+        MatrixClient::with_pacing(transport, Pacing::default())
+    }
+
+    pub fn with_pacing(transport: T, pacing: Pacing) -> Self {
+        MatrixClient {
+            transport,
+            pacing,
+            mode: AckMode::Unknown,
+            silent: 0,
+            decoder: FrameDecoder::new(),
+        }
+    }
+
+    pub fn pacing(&self) -> Pacing {
+        self.pacing
+    }
+
+    pub fn ack_mode(&self) -> AckMode {
+        self.mode
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    pub fn transport_mut(&mut self) -> &mut T {
+        &mut self.transport
+    }
+
+    pub fn into_transport(self) -> T {
+        self.transport
+    }
+
+    /// Checks that the device answers and returns its protocol version.
+    pub fn ping(&mut self) -> Result<u8, ClientError> {
+        match self.request(&serialize_ping()?)? {
+            Response::Pong { version } => Ok(version),
+            response => Err(ClientError::UnexpectedResponse(response)),
+        }
+    }
+
+    pub fn params(&mut self) -> Result<DeviceParams, ClientError> {
+        match self.request(&serialize_param_request()?)? {
+            Response::ParamReport(params) => Ok(params),
+            response => Err(ClientError::UnexpectedResponse(response)),
+        }
+    }
+
+    /// Sends any command and returns the device's reply, `None` if it does not acknowledge.
+    pub fn send(&mut self, command: &Command) -> Result<Option<Response>, ClientError> {
+        self.send_frame(&serialize_command(command)?)
+    }
+
+    /// Like `send`, for a frame that was already serialized.
+    pub fn send_frame(&mut self, frame: &Frame) -> Result<Option<Response>, ClientError> {
+        if is_request(frame[FRAME_HEADER.len() + 2]) {
+            self.request(frame).map(Some)
+        } else {
+            self.deliver(frame)
+        }
+    }
+
+    pub fn switch_mode(&mut self, mode: DisplayMode) -> Result<(), ClientError> {
+        self.deliver(&serialize_switch_mode(mode)?).map(drop)
+    }
+
+    pub fn write_line(&mut self, row: u8, text: &str) -> Result<(), ClientError> {
+        self.deliver(&serialize_write_line(row, text)?).map(drop)
+    }
+
+    pub fn set_font(&mut self, row: u8, font: FontType) -> Result<(), ClientError> {
+        self.deliver(&serialize_set_font(row, font.into())?)
+            .map(drop)
+    }
+
+    pub fn set_color(&mut self, row: u8, color: RgbColor) -> Result<(), ClientError> {
+        self.deliver(&serialize_set_color(row, color)?).map(drop)
+    }
+
+    pub fn set_animation(&mut self, row: u8, animation: Animation) -> Result<(), ClientError> {
+        self.deliver(&serialize_set_animation(row, animation)?)
+            .map(drop)
+    }
+
+    pub fn set_output(&mut self, enabled: bool) -> Result<(), ClientError> {
+        let frame = if enabled {
+            serialize_enable_output()?
+        } else {
+            serialize_disable_output()?
+        };
+
+        self.deliver(&frame).map(drop)
+    }
+
+    pub fn clear(&mut self) -> Result<(), ClientError> {
+        self.deliver(&serialize_clear()?).map(drop)
+    }
+
+    pub fn draw_pixel(&mut self, position: Point, color: RgbColor) -> Result<(), ClientError> {
+        self.deliver(&serialize_draw_pixel(position, color)?)
+            .map(drop)
+    }
+
+    pub fn draw_line(
+        &mut self,
+        point_1: Point,
+        point_2: Point,
+        thickness: u8,
+        color: RgbColor,
+    ) -> Result<(), ClientError> {
+        let frame = serialize_draw_line(point_1, point_2, thickness, color)?;
+        self.deliver(&frame).map(drop)
+    }
+
+    pub fn draw_rectangle(
+        &mut self,
+        point_1: Point,
+        point_2: Point,
+        thickness: u8,
+        color: RgbColor,
+        filled: bool,
+    ) -> Result<(), ClientError> {
+        let frame = serialize_draw_rectangle(point_1, point_2, thickness, color, filled)?;
+        self.deliver(&frame).map(drop)
+    }
+
+    pub fn draw_triangle(
+        &mut self,
+        point_1: Point,
+        point_2: Point,
+        point_3: Point,
+        thickness: u8,
+        color: RgbColor,
+        filled: bool,
+    ) -> Result<(), ClientError> {
+        let frame = serialize_draw_triangle(point_1, point_2, point_3, thickness, color, filled)?;
+        self.deliver(&frame).map(drop)
+    }
+
+    pub fn draw_circle(
+        &mut self,
+        center: Point,
+        radius: u8,
+        thickness: u8,
+        color: RgbColor,
+        filled: bool,
+    ) -> Result<(), ClientError> {
+        let frame = serialize_draw_circle(center, radius, thickness, color, filled)?;
+        self.deliver(&frame).map(drop)
+    }
+
+    pub fn draw_row<I>(&mut self, row: u8, pixels: I) -> Result<(), ClientError>
+    where
+        I: IntoIterator<Item = (u8, u8, u8)>,
+    {
+        self.deliver(&serialize_draw_row(row, pixels)?).map(drop)
+    }
+
+    /// Draws an image row by row, starting at the top of the panel.
+    ///
+    /// Nothing is sent if the image has more rows than a row index can address.
+    pub fn upload_image<I, R>(&mut self, rows: I) -> Result<(), ClientError>
+    where
+        I: IntoIterator<Item = R>,
+        R: IntoIterator<Item = (u8, u8, u8)>,
+    {
+        let rows: Vec<R> = rows.into_iter().collect();
+        let max = u8::MAX as usize + 1;
+        if rows.len() > max {
+            return Err(ClientError::ImageTooTall {
+                height: rows.len(),
+                max,
+            });
+        }
+
+        for (i, row) in rows.into_iter().enumerate() {
+            self.draw_row(i as u8, row)?;
+        }
+
+        Ok(())
+    }
+
+    /// Sends a command frame, falling back to timed pacing for firmware that never answers.
+    fn deliver(&mut self, frame: &Frame) -> Result<Option<Response>, ClientError> {
+        let opcode = frame[FRAME_HEADER.len() + 2];
+
+        if self.mode == AckMode::Timed {
+            self.transport.send(frame)?;
+            thread::sleep(self.pacing.fallback_delay(opcode));
+            return Ok(None);
+        }
+
+        match self.exchange(frame, opcode) {
+            Ok(response) => Ok(Some(response)),
+            //Silence from a device that never answered might mean it does not acknowledge at
+            //all, but one lost frame or ACK is not enough to tell
+            Err(ClientError::NoResponse) if self.mode == AckMode::Unknown => {
+                self.silent += 1;
+                if self.silent >= SILENT_COMMANDS_BEFORE_TIMED {
+                    self.mode = AckMode::Timed;
+                }
+                thread::sleep(self.pacing.fallback_delay(opcode));
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Sends a frame that has to be answered, like a ping or a parameter request.
+    fn request(&mut self, frame: &Frame) -> Result<Response, ClientError> {
+        self.exchange(frame, frame[FRAME_HEADER.len() + 2])
+    }
+
+    /// Sends `frame` and waits for its reply, retransmitting it after a timeout or a NACK
+    /// that might not happen again. A command that times out before the device has
+    /// acknowledged anything is not retransmitted, see `deliver`.
+    fn exchange(&mut self, frame: &[u8], opcode: u8) -> Result<Response, ClientError> {
+        let mut error = ClientError::NoResponse;
+
+        for _ in 0..=self.pacing.retries {
+            //A late reply to an earlier attempt would be taken for this one's
+            self.transport.discard_input()?;
+            self.decoder.reset();
+            self.transport.send(frame)?;
+
+            let response = match self.await_response(opcode)? {
+                Some(response) => response,
+                None => {
+                    error = ClientError::NoResponse;
+                    //Firmware that does not acknowledge would only stay silent again
+                    if self.mode == AckMode::Unknown && !is_request(opcode) {
+                        break;
+                    }
+                    continue;
+                }
+            };
+
+            match response {
+                Response::Nack { reason, .. } => {
+                    self.mode = AckMode::Acknowledged;
+                    error = ClientError::Rejected(reason);
+                    if !is_transient(reason) {
+                        break;
+                    }
+                }
+                Response::Ack { .. } => {
+                    self.mode = AckMode::Acknowledged;
+                    return Ok(response);
+                }
+                _ => return Ok(response),
+            }
+        }
+
+        Err(error)
+    }
+
+    fn await_response(&mut self, opcode: u8) -> io::Result<Option<Response>> {
+        let deadline = Instant::now() + self.pacing.ack_timeout;
+        let mut buffer = [0; 64];
+        let mut response = None;
+
+        while response.is_none() {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+
+            let count = self.transport.receive(&mut buffer, deadline - now)?;
+            self.decoder.feed(&buffer[..count], |payload| {
+                if response.is_none() {
+                    response = Response::decode(payload)
+                        .ok()
+                        .filter(|response| answers(opcode, response));
+                }
+            });
+        }
+
+        Ok(response)
+    }
+}
+
+/// Whether a frame with `opcode` asks for data, so it is answered even by old firmware.
+pub(crate) fn is_request(opcode: u8) -> bool {
+    opcode == Opcode::Ping.into() || opcode == Opcode::ParamRequest.into()
+}
+
+/// Whether `response` is the reply to a frame with `opcode`, rather than a stale one.
+pub(crate) fn answers(opcode: u8, response: &Response) -> bool {
+    match response {
+        Response::Ack { opcode: answered }
+        | Response::Nack {
+            opcode: answered, ..
+        } => *answered == opcode,
+        Response::Pong { .. } => opcode == Opcode::Ping.into(),
+        Response::ParamReport(_) => opcode == Opcode::ParamRequest.into(),
+    }
+}
+
+//Rejections of the command itself would only repeat, retrying is pointless
+pub(crate) fn is_transient(reason: NackReason) -> bool {
+    matches!(reason, NackReason::BadCrc | NackReason::BufferFull)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+    use crate::{serialize_response, PROTOCOL_VERSION};
+
+    /// Answers every frame with the next scripted reply, `None` stays silent.
+    struct FakeDevice {
+        replies: VecDeque<Option<Response>>,
+        sent: Vec<Vec<u8>>,
+        pending: Vec<u8>,
+    }
+
+    impl FakeDevice {
+        fn new(replies: Vec<Option<Response>>) -> Self {
+            FakeDevice {
+                replies: replies.into(),
+                sent: vec![],
+                pending: vec![],
+            }
+        }
+    }
+
+    impl Transport for FakeDevice {
+        fn send(&mut self, data: &[u8]) -> io::Result<()> {
+            self.sent.push(data.to_vec());
+            if let Some(Some(response)) = self.replies.pop_front() {
+                let frame = serialize_response(&response).unwrap();
+                self.pending.extend_from_slice(&frame);
+            }
+            Ok(())
+        }
+
+        fn receive(&mut self, buffer: &mut [u8], timeout: Duration) -> io::Result<usize> {
+            if self.pending.is_empty() {
+                thread::sleep(timeout);
+                return Ok(0);
+            }
+
+            let count = self.pending.len().min(buffer.len());
+            buffer[..count].clone_from_slice(&self.pending[..count]);
+            self.pending.drain(..count);
+            Ok(count)
+        }
+    }
+
+    fn client(replies: Vec<Option<Response>>) -> MatrixClient<FakeDevice> {
+        MatrixClient::with_pacing(
+            FakeDevice::new(replies),
+            Pacing {
+                ack_timeout: Duration::from_millis(5),
+                retries: 2,
+                command_delay: Duration::from_millis(0),
+                draw_delay: Duration::from_millis(0),
+            },
+        )
+    }
+
+    fn ack(opcode: Opcode) -> Option<Response> {
+        Some(Response::Ack {
+            opcode: opcode.into(),
+        })
+    }
+
+    fn nack(opcode: Opcode, reason: NackReason) -> Option<Response> {
+        Some(Response::Nack {
+            opcode: opcode.into(),
+            reason,
+        })
+    }
+
+    #[test]
+    fn sends_framed_commands_and_waits_for_ack() {
+        let mut client = client(vec![ack(Opcode::WriteLine), ack(Opcode::Clear)]);
+
+        client.write_line(1, "HELLO").unwrap();
+        client.clear().unwrap();
+
+        assert_eq!(client.ack_mode(), AckMode::Acknowledged);
+        assert_eq!(
+            client.transport().sent,
+            vec![
+                serialize_write_line(1, "HELLO").unwrap().to_vec(),
+                serialize_clear().unwrap().to_vec()
+            ]
+        );
+    }
+
+    #[test]
+    fn retransmits_after_timeout_and_corruption() {
+        let mut client = client(vec![
+            None,
+            nack(Opcode::SetColor, NackReason::BadCrc),
+            ack(Opcode::SetColor),
+        ]);
+        client.mode = AckMode::Acknowledged;
+
+        client.set_color(0, RgbColor::new()).unwrap();
+        assert_eq!(client.transport().sent.len(), 3);
+    }
+
+    #[test]
+    fn reports_rejected_commands_without_retrying() {
+        let mut client = client(vec![nack(Opcode::SetFont, NackReason::BadRow)]);
+
+        let result = client.set_font(7, FontType::Pro);
+        assert!(matches!(
+            result,
+            Err(ClientError::Rejected(NackReason::BadRow))
+        ));
+        assert_eq!(client.transport().sent.len(), 1);
+    }
+
+    #[test]
+    fn ignores_replies_to_other_frames() {
+        let mut client = client(vec![ack(Opcode::Clear), None, None]);
+        client.mode = AckMode::Acknowledged;
+
+        let result = client.draw_pixel(Point { x: 1, y: 1 }, RgbColor::new());
+        assert!(matches!(result, Err(ClientError::NoResponse)));
+        assert_eq!(client.transport().sent.len(), 3);
+    }
+
+    #[test]
+    fn falls_back_to_timed_pacing_for_silent_firmware() {
+        let mut client = client(vec![]);
+
+        for _ in 0..SILENT_COMMANDS_BEFORE_TIMED {
+            assert_eq!(client.ack_mode(), AckMode::Unknown);
+            client.clear().unwrap();
+        }
+        assert_eq!(client.ack_mode(), AckMode::Timed);
+        assert_eq!(client.transport().sent.len(), 3);
+
+        client.upload_image(vec![vec![(1, 2, 3); 4]; 2]).unwrap();
+        assert_eq!(client.transport().sent.len(), 5);
+        assert_eq!(
+            client.transport().sent[4],
+            serialize_draw_row(1, vec![(1, 2, 3); 4]).unwrap().to_vec()
+        );
+    }
+
+    #[test]
+    fn keeps_acknowledging_after_a_lost_first_ack() {
+        let mut client = client(vec![None, ack(Opcode::SetColor), None, ack(Opcode::Clear)]);
+
+        client.clear().unwrap();
+        assert_eq!(client.ack_mode(), AckMode::Unknown);
+        client.set_color(0, RgbColor::new()).unwrap();
+        assert_eq!(client.ack_mode(), AckMode::Acknowledged);
+
+        //Losses are retransmitted from now on
+        client.clear().unwrap();
+        assert_eq!(client.ack_mode(), AckMode::Acknowledged);
+        assert_eq!(client.transport().sent.len(), 4);
+    }
+
+    #[test]
+    fn rejects_images_taller_than_the_row_index() {
+        let mut client = client(vec![]);
+        client.mode = AckMode::Timed;
+
+        let result = client.upload_image(vec![vec![(0, 0, 0); 1]; 257]);
+        assert!(matches!(
+            result,
+            Err(ClientError::ImageTooTall {
+                height: 257,
+                max: 256
+            })
+        ));
+        assert!(client.transport().sent.is_empty());
+
+        client.upload_image(vec![vec![(0, 0, 0); 1]; 256]).unwrap();
+        assert_eq!(client.transport().sent.len(), 256);
+    }
+
+    #[test]
+    fn requests_need_an_answer() {
+        let params = DeviceParams::default();
+        let mut client = client(vec![
+            Some(Response::Pong {
+                version: PROTOCOL_VERSION,
+            }),
+            Some(Response::ParamReport(params)),
+        ]);
+
+        assert_eq!(client.ping().unwrap(), PROTOCOL_VERSION);
+        assert_eq!(client.params().unwrap(), params);
+        assert_eq!(client.ack_mode(), AckMode::Unknown);
+        assert!(matches!(client.ping(), Err(ClientError::NoResponse)));
+    }
+}
@@ -0,0 +1,459 @@
+use core::convert::TryFrom;
+
+use crate::enums::{Animation, Direction, DisplayMode, FontType, Opcode};
+use crate::error::UmxError;
+use crate::writer::PayloadWriter;
+use crate::{Point, RgbColor, MAX_ROW_PIXELS, MAX_TEXT_LENGTH};
+
+/// A single UMX operation, independent of its wire representation.
+///
+/// `encode` produces the frame payload (opcode followed by arguments) and `decode`
+/// parses it back, so a command survives an encode/decode round-trip unchanged.
+/// Text and pixel data use fixed-capacity storage so no allocator is needed.
+//Boxing the row pixels would defeat the point of fixed-capacity storage
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    ParamRequest,
+    SwitchMode(DisplayMode),
+    WriteLine {
+        row: u8,
+        text: heapless::String<MAX_TEXT_LENGTH>,
+    },
+    SetFont {
+        row: u8,
+        font: FontType,
+    },
+    SetColor {
+        row: u8,
+        color: RgbColor,
+    },
+    SetAnimation {
+        row: u8,
+        animation: Animation,
+    },
+    DrawPixel {
+        position: Point,
+        color: RgbColor,
+    },
+    DrawRow {
+        row: u8,
+        pixels: heapless::Vec<RgbColor, MAX_ROW_PIXELS>,
+    },
+    DrawLine {
+        point_1: Point,
+        point_2: Point,
+        thickness: u8,
+        color: RgbColor,
+    },
+    DrawRectangle {
+        point_1: Point,
+        point_2: Point,
+        thickness: u8,
+        color: RgbColor,
+        filled: bool,
+    },
+    DrawTriangle {
+        point_1: Point,
+        point_2: Point,
+        point_3: Point,
+        thickness: u8,
+        color: RgbColor,
+        filled: bool,
+    },
+    DrawCircle {
+        center: Point,
+        radius: u8,
+        thickness: u8,
+        color: RgbColor,
+        filled: bool,
+    },
+    Clear,
+    EnableOutput,
+    DisableOutput,
+    /// Carries the sender's protocol version, so the device can detect a table mismatch.
+    Ping {
+        version: u8,
+    },
+}
+
+impl Command {
+    pub fn opcode(&self) -> Opcode {
+        match self {
+            Command::ParamRequest => Opcode::ParamRequest,
+            Command::SwitchMode(_) => Opcode::SwitchMode,
+            Command::WriteLine { .. } => Opcode::WriteLine,
+            Command::SetFont { .. } => Opcode::SetFont,
+            Command::SetColor { .. } => Opcode::SetColor,
+            Command::SetAnimation { .. } => Opcode::SetAnimation,
+            Command::DrawPixel { .. } => Opcode::DrawPixel,
+            Command::DrawRow { .. } => Opcode::DrawRow,
+            Command::DrawLine { .. } => Opcode::DrawLine,
+            Command::DrawRectangle { .. } => Opcode::DrawRectangle,
+            Command::DrawTriangle { .. } => Opcode::DrawTriangle,
+            Command::DrawCircle { .. } => Opcode::DrawCircle,
+            Command::Clear => Opcode::Clear,
+            Command::EnableOutput => Opcode::EnableOutput,
+            Command::DisableOutput => Opcode::DisableOutput,
+            Command::Ping { .. } => Opcode::Ping,
+        }
+    }
+
+    #[cfg(any(test, feature = "std"))]
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buffer = [0; crate::MAX_PAYLOAD_SIZE];
+        let length = self
+            .encode_into(&mut buffer)
+            .expect("every command fits in a single payload");
+
+        buffer[..length].to_vec()
+    }
+
+    /// Writes the payload into `buffer` and returns its length.
+    pub fn encode_into(&self, buffer: &mut [u8]) -> Result<usize, UmxError> {
+        let mut data = PayloadWriter::new(buffer);
+        data.push(self.opcode().into());
+
+        match self {
+            Command::ParamRequest | Command::Clear => {}
+            Command::EnableOutput | Command::DisableOutput => {}
+            Command::SwitchMode(mode) => data.push((*mode).into()),
+            Command::WriteLine { row, text } => {
+                data.push(*row);
+                data.extend(text.as_bytes());
+            }
+            Command::SetFont { row, font } => data.extend(&[*row, (*font).into()]),
+            Command::SetColor { row, color } => data.extend(&[*row, color.r, color.g, color.b]),
+            Command::SetAnimation { row, animation } => {
+                data.push(*row);
+                match animation {
+                    Animation::NoAnimation => data.push(0),
+                    Animation::BlinkAnimation(speed) => data.extend(&[1, *speed]),
+                    Animation::SlideAnimation(speed, direction) => {
+                        let dir = match direction {
+                            Direction::Left => 0,
+                            Direction::Right => 1,
+                        };
+                        data.extend(&[2, *speed, dir]);
+                    }
+                }
+            }
+            Command::DrawPixel { position, color } => {
+                data.extend(&[position.x, position.y, color.r, color.g, color.b])
+            }
+            Command::DrawRow { row, pixels } => {
+                data.push(*row);
+                for pixel in pixels {
+                    data.extend(&[pixel.r, pixel.g, pixel.b]);
+                }
+            }
+            Command::DrawLine {
+                point_1,
+                point_2,
+                thickness,
+                color,
+            } => data.extend(&[
+                point_1.x, point_1.y, point_2.x, point_2.y, *thickness, color.r, color.g, color.b,
+            ]),
+            Command::DrawRectangle {
+                point_1,
+                point_2,
+                thickness,
+                color,
+                filled,
+            } => data.extend(&[
+                point_1.x,
+                point_1.y,
+                point_2.x,
+                point_2.y,
+                *thickness,
+                color.r,
+                color.g,
+                color.b,
+                *filled as u8,
+            ]),
+            Command::DrawTriangle {
+                point_1,
+                point_2,
+                point_3,
+                thickness,
+                color,
+                filled,
+            } => data.extend(&[
+                point_1.x,
+                point_1.y,
+                point_2.x,
+                point_2.y,
+                point_3.x,
+                point_3.y,
+                *thickness,
+                color.r,
+                color.g,
+                color.b,
+                *filled as u8,
+            ]),
+            Command::DrawCircle {
+                center,
+                radius,
+                thickness,
+                color,
+                filled,
+            } => data.extend(&[
+                center.x,
+                center.y,
+                *radius,
+                *thickness,
+                color.r,
+                color.g,
+                color.b,
+                *filled as u8,
+            ]),
+            Command::Ping { version } => data.push(*version),
+        }
+
+        data.finish()
+    }
+
+    pub fn decode(payload: &[u8]) -> Result<Command, UmxError> {
+        let (opcode, args) = payload.split_first().ok_or(UmxError::MalformedPayload)?;
+        let opcode = Opcode::try_from(*opcode).map_err(UmxError::UnknownOpcode)?;
+
+        decode_args(opcode, args).ok_or(UmxError::MalformedPayload)
+    }
+}
+
+fn decode_args(opcode: Opcode, args: &[u8]) -> Option<Command> {
+    let command = match (opcode, args) {
+        (Opcode::ParamRequest, []) => Command::ParamRequest,
+        (Opcode::SwitchMode, [mode]) => Command::SwitchMode(DisplayMode::try_from(*mode).ok()?),
+        (Opcode::WriteLine, [row, text @ ..]) => {
+            let mut line = heapless::String::new();
+            line.push_str(core::str::from_utf8(text).ok()?).ok()?;
+            Command::WriteLine {
+                row: *row,
+                text: line,
+            }
+        }
+        (Opcode::SetFont, [row, font]) => Command::SetFont {
+            row: *row,
+            font: FontType::try_from(*font).ok()?,
+        },
+        (Opcode::SetColor, [row, r, g, b]) => Command::SetColor {
+            row: *row,
+            color: RgbColor::from_rgb(*r, *g, *b),
+        },
+        (Opcode::SetAnimation, [row, anim_data @ ..]) => Command::SetAnimation {
+            row: *row,
+            animation: decode_animation(anim_data)?,
+        },
+        (Opcode::DrawPixel, [x, y, r, g, b]) => Command::DrawPixel {
+            position: Point { x: *x, y: *y },
+            color: RgbColor::from_rgb(*r, *g, *b),
+        },
+        (Opcode::DrawRow, [row, pixels @ ..]) if pixels.len() % 3 == 0 => {
+            let mut row_pixels = heapless::Vec::new();
+            for pixel in pixels.chunks(3) {
+                row_pixels
+                    .push(RgbColor::from_rgb(pixel[0], pixel[1], pixel[2]))
+                    .ok()?;
+            }
+            Command::DrawRow {
+                row: *row,
+                pixels: row_pixels,
+            }
+        }
+        (Opcode::DrawLine, [x_1, y_1, x_2, y_2, thickness, r, g, b]) => Command::DrawLine {
+            point_1: Point { x: *x_1, y: *y_1 },
+            point_2: Point { x: *x_2, y: *y_2 },
+            thickness: *thickness,
+            color: RgbColor::from_rgb(*r, *g, *b),
+        },
+        (Opcode::DrawRectangle, [x_1, y_1, x_2, y_2, thickness, r, g, b, filled]) => {
+            Command::DrawRectangle {
+                point_1: Point { x: *x_1, y: *y_1 },
+                point_2: Point { x: *x_2, y: *y_2 },
+                thickness: *thickness,
+                color: RgbColor::from_rgb(*r, *g, *b),
+                filled: decode_bool(*filled)?,
+            }
+        }
+        (Opcode::DrawTriangle, [x_1, y_1, x_2, y_2, x_3, y_3, thickness, r, g, b, filled]) => {
+            Command::DrawTriangle {
+                point_1: Point { x: *x_1, y: *y_1 },
+                point_2: Point { x: *x_2, y: *y_2 },
+                point_3: Point { x: *x_3, y: *y_3 },
+                thickness: *thickness,
+                color: RgbColor::from_rgb(*r, *g, *b),
+                filled: decode_bool(*filled)?,
+            }
+        }
+        (Opcode::DrawCircle, [x, y, radius, thickness, r, g, b, filled]) => Command::DrawCircle {
+            center: Point { x: *x, y: *y },
+            radius: *radius,
+            thickness: *thickness,
+            color: RgbColor::from_rgb(*r, *g, *b),
+            filled: decode_bool(*filled)?,
+        },
+        (Opcode::Clear, []) => Command::Clear,
+        (Opcode::EnableOutput, []) => Command::EnableOutput,
+        (Opcode::DisableOutput, []) => Command::DisableOutput,
+        (Opcode::Ping, [version]) => Command::Ping { version: *version },
+        _ => return None,
+    };
+
+    Some(command)
+}
+
+fn decode_bool(value: u8) -> Option<bool> {
+    match value {
+        0 => Some(false),
+        1 => Some(true),
+        _ => None,
+    }
+}
+
+fn decode_animation(data: &[u8]) -> Option<Animation> {
+    match data {
+        [0] => Some(Animation::NoAnimation),
+        [1, speed] => Some(Animation::BlinkAnimation(*speed)),
+        [2, speed, direction] => {
+            let direction = match direction {
+                0 => Direction::Left,
+                1 => Direction::Right,
+                _ => return None,
+            };
+            Some(Animation::SlideAnimation(*speed, direction))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PROTOCOL_VERSION;
+
+    fn all_commands() -> Vec<Command> {
+        let color = RgbColor::from_rgb(1, 2, 3);
+        vec![
+            Command::ParamRequest,
+            Command::SwitchMode(DisplayMode::Direct),
+            Command::WriteLine {
+                row: 2,
+                text: heapless::String::from("Zażółć"),
+            },
+            Command::SetFont {
+                row: 1,
+                font: FontType::Ibm,
+            },
+            Command::SetColor { row: 0, color },
+            Command::SetAnimation {
+                row: 1,
+                animation: Animation::NoAnimation,
+            },
+            Command::SetAnimation {
+                row: 1,
+                animation: Animation::BlinkAnimation(4),
+            },
+            Command::SetAnimation {
+                row: 2,
+                animation: Animation::SlideAnimation(30, Direction::Right),
+            },
+            Command::DrawPixel {
+                position: Point { x: 5, y: 6 },
+                color,
+            },
+            Command::DrawRow {
+                row: 31,
+                pixels: core::iter::repeat_n(color, 64).collect(),
+            },
+            Command::DrawLine {
+                point_1: Point { x: 0, y: 0 },
+                point_2: Point { x: 63, y: 31 },
+                thickness: 2,
+                color,
+            },
+            Command::DrawRectangle {
+                point_1: Point { x: 1, y: 2 },
+                point_2: Point { x: 3, y: 4 },
+                thickness: 1,
+                color,
+                filled: true,
+            },
+            Command::DrawTriangle {
+                point_1: Point { x: 1, y: 2 },
+                point_2: Point { x: 3, y: 4 },
+                point_3: Point { x: 5, y: 6 },
+                thickness: 1,
+                color,
+                filled: false,
+            },
+            Command::DrawCircle {
+                center: Point { x: 32, y: 16 },
+                radius: 10,
+                thickness: 3,
+                color,
+                filled: true,
+            },
+            Command::Clear,
+            Command::EnableOutput,
+            Command::DisableOutput,
+            Command::Ping {
+                version: PROTOCOL_VERSION,
+            },
+        ]
+    }
+
+    #[test]
+    fn encode_decode_round_trip() {
+        for command in all_commands() {
+            assert_eq!(Command::decode(&command.encode()), Ok(command));
+        }
+    }
+
+    #[test]
+    fn decode_rejects_malformed_payloads() {
+        assert_eq!(Command::decode(&[]), Err(UmxError::MalformedPayload));
+        assert_eq!(Command::decode(&[1, 7]), Err(UmxError::MalformedPayload));
+        assert_eq!(
+            Command::decode(&[4, 0, 1, 2]),
+            Err(UmxError::MalformedPayload)
+        );
+        assert_eq!(
+            Command::decode(&[5, 0, 2, 10]),
+            Err(UmxError::MalformedPayload)
+        );
+        assert_eq!(
+            Command::decode(&[7, 0, 1, 2]),
+            Err(UmxError::MalformedPayload)
+        );
+        assert_eq!(
+            Command::decode(&[2, 0, 0xFF]),
+            Err(UmxError::MalformedPayload)
+        );
+        assert_eq!(Command::decode(&[200]), Err(UmxError::UnknownOpcode(200)));
+        assert_eq!(
+            Command::decode(&[Opcode::Ping.into()]),
+            Err(UmxError::MalformedPayload)
+        );
+        //Other protocol versions are the caller's to reject, not malformed
+        assert_eq!(
+            Command::decode(&[Opcode::Ping.into(), PROTOCOL_VERSION + 1]),
+            Ok(Command::Ping {
+                version: PROTOCOL_VERSION + 1
+            })
+        );
+    }
+
+    #[test]
+    fn opcodes_are_unique() {
+        let commands = all_commands();
+        for (i, command) in commands.iter().enumerate() {
+            let opcode = command.encode()[0];
+            let collision = commands[..i]
+                .iter()
+                .any(|other| other.encode()[0] == opcode && other.opcode() != command.opcode());
+            assert!(!collision, "{:?} shares opcode {}", command, opcode);
+        }
+    }
+}
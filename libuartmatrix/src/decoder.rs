@@ -0,0 +1,238 @@
+use crate::error::UmxError;
+use crate::{crc, FRAME_HEADER, FRAME_OVERHEAD, MAX_FRAME_SIZE, MAX_PAYLOAD_SIZE};
+
+enum FrameStatus {
+    Incomplete,
+    Invalid,
+    BadCrc(usize),
+    Complete(usize),
+}
+
+/// Streaming decoder for UMX frames.
+///
+/// Bytes can be fed in arbitrary chunks; every complete frame with a valid CRC is
+/// handed to the callback as its payload. Garbage, truncated frames and frames with
+/// a bad checksum are skipped and the decoder resynchronizes on the next `UMX` header.
+pub struct FrameDecoder {
+    buffer: [u8; MAX_FRAME_SIZE],
+    len: usize,
+}
+
+impl FrameDecoder {
+    pub fn new() -> Self {
+        FrameDecoder {
+            buffer: [0; MAX_FRAME_SIZE],
+            len: 0,
+        }
+    }
+
+    /// Drops any partially received frame.
+    pub fn reset(&mut self) {
+        self.len = 0;
+    }
+
+    pub fn push<F: FnMut(&[u8])>(&mut self, byte: u8, mut on_frame: F) {
+        self.push_checked(byte, |result| {
+            if let Ok(payload) = result {
+                on_frame(payload);
+            }
+        });
+    }
+
+    /// Like `push`, but also reports frames that were dropped because of a bad CRC.
+    ///
+    /// The error carries the first payload byte so a device can NACK the opcode; it is
+    /// only a best guess since the payload itself failed the checksum.
+    pub fn push_checked<F>(&mut self, byte: u8, mut on_frame: F)
+    where
+        F: FnMut(Result<&[u8], UmxError>),
+    {
+        if self.len == self.buffer.len() {
+            self.resync();
+        }
+
+        self.buffer[self.len] = byte;
+        self.len += 1;
+
+        loop {
+            match self.status() {
+                FrameStatus::Incomplete => break,
+                FrameStatus::Invalid => self.resync(),
+                FrameStatus::BadCrc(length) => {
+                    let opcode = if length > 0 { self.buffer[5] } else { 0 };
+                    on_frame(Err(UmxError::CrcMismatch { opcode }));
+                    self.resync();
+                }
+                FrameStatus::Complete(length) => {
+                    on_frame(Ok(&self.buffer[5..(length + 5)]));
+                    self.consume(length + FRAME_OVERHEAD);
+                }
+            }
+        }
+    }
+
+    pub fn feed<F: FnMut(&[u8])>(&mut self, data: &[u8], mut on_frame: F) {
+        for byte in data {
+            self.push(*byte, &mut on_frame);
+        }
+    }
+
+    pub fn feed_checked<F>(&mut self, data: &[u8], mut on_frame: F)
+    where
+        F: FnMut(Result<&[u8], UmxError>),
+    {
+        for byte in data {
+            self.push_checked(*byte, &mut on_frame);
+        }
+    }
+
+    fn status(&self) -> FrameStatus {
+        let header_len = self.len.min(FRAME_HEADER.len());
+        if self.buffer[..header_len] != FRAME_HEADER[..header_len] {
+            return FrameStatus::Invalid;
+        }
+
+        if self.len < 5 {
+            return FrameStatus::Incomplete;
+        }
+
+        let length = ((self.buffer[3] as usize) << 8) | self.buffer[4] as usize;
+        if length > MAX_PAYLOAD_SIZE {
+            return FrameStatus::Invalid;
+        }
+
+        if self.len < length + FRAME_OVERHEAD {
+            return FrameStatus::Incomplete;
+        }
+
+        if crc::crc8_ccitt(&self.buffer[5..(length + 5)]) != self.buffer[length + 5] {
+            return FrameStatus::BadCrc(length);
+        }
+
+        FrameStatus::Complete(length)
+    }
+
+    //Skip the current header candidate and restart at the next 'U' in the buffer
+    fn resync(&mut self) {
+        let next_start = self.buffer[1..self.len]
+            .iter()
+            .position(|byte| *byte == FRAME_HEADER[0])
+            .map(|position| position + 1)
+            .unwrap_or(self.len);
+
+        self.consume(next_start);
+    }
+
+    fn consume(&mut self, count: usize) {
+        self.buffer.copy_within(count..self.len, 0);
+        self.len -= count;
+    }
+}
+
+impl Default for FrameDecoder {
+    fn default() -> Self {
+        FrameDecoder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialize_write_line;
+
+    fn frame(payload: &[u8]) -> Vec<u8> {
+        let mut frame = vec![
+            b'U',
+            b'M',
+            b'X',
+            (payload.len() >> 8) as u8,
+            payload.len() as u8,
+        ];
+        frame.extend_from_slice(payload);
+        frame.push(crc::crc8_ccitt(payload));
+        frame
+    }
+
+    fn decode_all(decoder: &mut FrameDecoder, data: &[u8]) -> Vec<Vec<u8>> {
+        let mut frames = vec![];
+        decoder.feed(data, |payload| frames.push(payload.to_vec()));
+        frames
+    }
+
+    #[test]
+    fn decodes_serialized_frame() {
+        let packet = serialize_write_line(1, "HELLO").unwrap();
+        let mut decoder = FrameDecoder::new();
+
+        let frames = decode_all(&mut decoder, &packet);
+        assert_eq!(frames, vec![[&[2, 1], "HELLO".as_bytes()].concat()]);
+    }
+
+    #[test]
+    fn decodes_frames_split_across_chunks() {
+        let data = [frame(&[1, 2, 3]), frame(&[4])].concat();
+        let mut decoder = FrameDecoder::new();
+        let mut frames = vec![];
+
+        for chunk in data.chunks(2) {
+            frames.extend(decode_all(&mut decoder, chunk));
+        }
+
+        assert_eq!(frames, vec![vec![1, 2, 3], vec![4]]);
+    }
+
+    #[test]
+    fn resyncs_after_garbage_and_bad_crc() {
+        let mut corrupted = frame(&[7, 7, 7]);
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0xFF;
+
+        let data = [
+            &b"UUMU\x00garbage"[..],
+            &corrupted,
+            &frame(&[5, 6])[..3],
+            &frame(&[8, 9]),
+        ]
+        .concat();
+        let mut decoder = FrameDecoder::new();
+
+        assert_eq!(decode_all(&mut decoder, &data), vec![vec![8, 9]]);
+    }
+
+    #[test]
+    fn reports_bad_crc() {
+        let mut corrupted = frame(&[4, 1, 2]);
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0xFF;
+
+        let data = [&corrupted[..], &frame(&[12])].concat();
+        let mut decoder = FrameDecoder::new();
+        let mut results = vec![];
+        decoder.feed_checked(&data, |result| results.push(result.map(|p| p.to_vec())));
+
+        assert_eq!(
+            results,
+            vec![Err(UmxError::CrcMismatch { opcode: 4 }), Ok(vec![12])]
+        );
+    }
+
+    #[test]
+    fn recovers_frames_inside_truncated_frame() {
+        //A header announcing a long payload must not swallow the frames that follow it
+        let mut data = vec![b'U', b'M', b'X', 0, 200, 1, 2];
+        for _ in 0..40 {
+            data.extend(frame(&[3]));
+        }
+        let mut decoder = FrameDecoder::new();
+
+        assert_eq!(decode_all(&mut decoder, &data), vec![vec![3]; 40]);
+    }
+
+    #[test]
+    fn rejects_oversized_length() {
+        let data = [&[b'U', b'M', b'X', 0xFF, 0xFF][..], &frame(&[11])].concat();
+        let mut decoder = FrameDecoder::new();
+
+        assert_eq!(decode_all(&mut decoder, &data), vec![vec![11]]);
+    }
+}
@@ -1,3 +1,97 @@
+use core::convert::TryFrom;
+
+/// Opcode table, one entry per command. Bump `PROTOCOL_VERSION` whenever it changes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Opcode {
+    ParamRequest = 0,
+    SwitchMode = 1,
+    WriteLine = 2,
+    SetFont = 3,
+    SetColor = 4,
+    SetAnimation = 5,
+    DrawPixel = 6,
+    DrawRow = 7,
+    DrawLine = 8,
+    DrawRectangle = 9,
+    DrawTriangle = 10,
+    DrawCircle = 11,
+    Clear = 12,
+    EnableOutput = 13,
+    DisableOutput = 14,
+    Ping = 15,
+}
+
+impl Opcode {
+    pub const ALL: [Opcode; 16] = [
+        Opcode::ParamRequest,
+        Opcode::SwitchMode,
+        Opcode::WriteLine,
+        Opcode::SetFont,
+        Opcode::SetColor,
+        Opcode::SetAnimation,
+        Opcode::DrawPixel,
+        Opcode::DrawRow,
+        Opcode::DrawLine,
+        Opcode::DrawRectangle,
+        Opcode::DrawTriangle,
+        Opcode::DrawCircle,
+        Opcode::Clear,
+        Opcode::EnableOutput,
+        Opcode::DisableOutput,
+        Opcode::Ping,
+    ];
+}
+
+impl From<Opcode> for u8 {
+    fn from(opcode: Opcode) -> u8 {
+        opcode as u8
+    }
+}
+
+impl TryFrom<u8> for Opcode {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Opcode::ALL
+            .iter()
+            .find(|opcode| **opcode as u8 == value)
+            .copied()
+            .ok_or(value)
+    }
+}
+
+/// First payload byte of every frame sent by the device.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ResponseCode {
+    Ack = 0x80,
+    Nack = 0x81,
+    Pong = 0x82,
+    ParamReport = 0x83,
+}
+
+impl From<ResponseCode> for u8 {
+    fn from(code: ResponseCode) -> u8 {
+        code as u8
+    }
+}
+
+impl TryFrom<u8> for ResponseCode {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x80 => Ok(ResponseCode::Ack),
+            0x81 => Ok(ResponseCode::Nack),
+            0x82 => Ok(ResponseCode::Pong),
+            0x83 => Ok(ResponseCode::ParamReport),
+            _ => Err(value),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum DisplayMode {
     Text,
     Direct,
@@ -12,12 +106,29 @@ impl Into<u8> for DisplayMode {
     }
 }
 
+impl TryFrom<u8> for DisplayMode {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(DisplayMode::Text),
+            1 => Ok(DisplayMode::Direct),
+            _ => Err(value),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum FontType {
     Default,
     Pro,
     Ibm,
 }
 
+impl FontType {
+    pub const ALL: [FontType; 3] = [FontType::Default, FontType::Pro, FontType::Ibm];
+}
+
 impl Into<u8> for FontType {
     fn into(self) -> u8 {
         match self {
@@ -28,17 +139,33 @@ impl Into<u8> for FontType {
     }
 }
 
+impl TryFrom<u8> for FontType {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(FontType::Default),
+            1 => Ok(FontType::Pro),
+            2 => Ok(FontType::Ibm),
+            _ => Err(value),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Direction {
     Left,
     Right,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Animation {
     NoAnimation,
     BlinkAnimation(u8),
     SlideAnimation(u8, Direction),
 }
 
+#[cfg(feature = "std")]
 impl Into<Vec<u8>> for Animation {
     fn into(self) -> Vec<u8> {
         match self {
@@ -54,4 +181,4 @@ impl Into<Vec<u8>> for Animation {
             }
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,75 @@
+use core::ffi::c_int;
+use core::fmt;
+
+pub const UMX_ERR_TEXT_TOO_LONG: c_int = -1;
+pub const UMX_ERR_FRAME_TOO_LARGE: c_int = -2;
+pub const UMX_ERR_INVALID_FONT: c_int = -3;
+pub const UMX_ERR_INVALID_UTF8: c_int = -4;
+pub const UMX_ERR_UNKNOWN_OPCODE: c_int = -5;
+pub const UMX_ERR_UNKNOWN_RESPONSE: c_int = -6;
+pub const UMX_ERR_MALFORMED_PAYLOAD: c_int = -7;
+pub const UMX_ERR_NULL_POINTER: c_int = -8;
+pub const UMX_ERR_BUFFER_TOO_SMALL: c_int = -9;
+pub const UMX_ERR_CRC_MISMATCH: c_int = -10;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UmxError {
+    TextTooLong { length: usize, max: usize },
+    FrameTooLarge { length: usize, max: usize },
+    InvalidFont(u8),
+    InvalidUtf8,
+    UnknownOpcode(u8),
+    UnknownResponse(u8),
+    MalformedPayload,
+    NullPointer,
+    BufferTooSmall { needed: usize, available: usize },
+    CrcMismatch { opcode: u8 },
+}
+
+impl UmxError {
+    /// Negative return code used by the C interface.
+    pub fn code(&self) -> c_int {
+        match self {
+            UmxError::TextTooLong { .. } => UMX_ERR_TEXT_TOO_LONG,
+            UmxError::FrameTooLarge { .. } => UMX_ERR_FRAME_TOO_LARGE,
+            UmxError::InvalidFont(_) => UMX_ERR_INVALID_FONT,
+            UmxError::InvalidUtf8 => UMX_ERR_INVALID_UTF8,
+            UmxError::UnknownOpcode(_) => UMX_ERR_UNKNOWN_OPCODE,
+            UmxError::UnknownResponse(_) => UMX_ERR_UNKNOWN_RESPONSE,
+            UmxError::MalformedPayload => UMX_ERR_MALFORMED_PAYLOAD,
+            UmxError::NullPointer => UMX_ERR_NULL_POINTER,
+            UmxError::BufferTooSmall { .. } => UMX_ERR_BUFFER_TOO_SMALL,
+            UmxError::CrcMismatch { .. } => UMX_ERR_CRC_MISMATCH,
+        }
+    }
+}
+
+impl fmt::Display for UmxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UmxError::TextTooLong { length, max } => {
+                write!(f, "text is {} bytes long, the limit is {}", length, max)
+            }
+            UmxError::FrameTooLarge { length, max } => {
+                write!(f, "payload is {} bytes long, the limit is {}", length, max)
+            }
+            UmxError::InvalidFont(font) => write!(f, "invalid font id {}", font),
+            UmxError::InvalidUtf8 => write!(f, "text is not valid UTF-8"),
+            UmxError::UnknownOpcode(opcode) => write!(f, "unknown opcode {}", opcode),
+            UmxError::UnknownResponse(code) => write!(f, "unknown response code {:#04x}", code),
+            UmxError::MalformedPayload => write!(f, "malformed payload"),
+            UmxError::NullPointer => write!(f, "null pointer passed to the C interface"),
+            UmxError::BufferTooSmall { needed, available } => write!(
+                f,
+                "buffer holds {} bytes but {} are needed",
+                available, needed
+            ),
+            UmxError::CrcMismatch { opcode } => {
+                write!(f, "checksum mismatch in frame with opcode {}", opcode)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UmxError {}
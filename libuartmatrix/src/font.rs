@@ -0,0 +1,164 @@
+use crate::enums::FontType;
+use crate::glyphs::{DEFAULT_GLYPHS, IBM_GLYPHS, PRO_GLYPHS};
+use crate::raster::RasterTarget;
+use crate::RgbColor;
+
+const FIRST_CHAR: char = ' ';
+const LAST_CHAR: char = '~';
+
+/// Monospaced bitmap font covering printable ASCII.
+///
+/// Default is misc-fixed 5x7, Pro is ProFont 7 point and Ibm the 8x8 IBM PC font.
+/// `advance` is the width of a character cell. Fonts add no gap between cells, so a
+/// string is `advance * chars` pixels wide, and glyphs such as '#' that fill their
+/// whole cell touch their neighbours.
+#[derive(Debug)]
+pub struct BitmapFont {
+    advance: u8,
+    height: u8,
+    glyphs: &'static [u8],
+}
+
+pub static DEFAULT_FONT: BitmapFont = BitmapFont {
+    advance: 5,
+    height: 7,
+    glyphs: &DEFAULT_GLYPHS,
+};
+
+pub static PRO_FONT: BitmapFont = BitmapFont {
+    advance: 5,
+    height: 10,
+    glyphs: &PRO_GLYPHS,
+};
+
+pub static IBM_FONT: BitmapFont = BitmapFont {
+    advance: 8,
+    height: 8,
+    glyphs: &IBM_GLYPHS,
+};
+
+impl FontType {
+    pub fn bitmap(self) -> &'static BitmapFont {
+        match self {
+            FontType::Default => &DEFAULT_FONT,
+            FontType::Pro => &PRO_FONT,
+            FontType::Ibm => &IBM_FONT,
+        }
+    }
+}
+
+impl BitmapFont {
+    /// Horizontal distance between the starts of two characters.
+    pub fn advance(&self) -> u32 {
+        self.advance as u32
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height as u32
+    }
+
+    pub fn supports(&self, c: char) -> bool {
+        (FIRST_CHAR..=LAST_CHAR).contains(&c)
+    }
+
+    /// First character of `text` this font has no glyph for.
+    pub fn unsupported_char(&self, text: &str) -> Option<char> {
+        text.chars().find(|c| !self.supports(*c))
+    }
+
+    pub fn text_width(&self, text: &str) -> u32 {
+        text.chars().count() as u32 * self.advance()
+    }
+
+    /// Rows of the glyph for `c`, the leftmost pixel is bit `advance - 1`.
+    pub fn glyph(&self, c: char) -> Option<&'static [u8]> {
+        if !self.supports(c) {
+            return None;
+        }
+
+        let start = (c as usize - FIRST_CHAR as usize) * self.height as usize;
+        Some(&self.glyphs[start..(start + self.height as usize)])
+    }
+
+    /// Draws `text` with its top left corner at (`x`, `y`), clipped to the target.
+    ///
+    /// Characters without a glyph are left blank but still take up their space.
+    pub fn render<T: RasterTarget + ?Sized>(
+        &self,
+        target: &mut T,
+        x: i32,
+        y: i32,
+        text: &str,
+        color: RgbColor,
+    ) {
+        for (i, c) in text.chars().enumerate() {
+            let glyph = match self.glyph(c) {
+                Some(glyph) => glyph,
+                None => continue,
+            };
+            let glyph_x = x + (i as u32 * self.advance()) as i32;
+
+            for (row, bits) in glyph.iter().enumerate() {
+                for column in 0..self.advance() {
+                    if bits & (1 << (self.advance() - 1 - column)) == 0 {
+                        continue;
+                    }
+
+                    let (px, py) = (glyph_x + column as i32, y + row as i32);
+                    if px >= 0
+                        && py >= 0
+                        && (px as u32) < target.width()
+                        && (py as u32) < target.height()
+                    {
+                        target.set_pixel(px as u32, py as u32, color);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raster::RgbBuffer;
+
+    #[test]
+    fn metrics_match_tables() {
+        for font in FontType::ALL.iter() {
+            let bitmap = font.bitmap();
+            assert_eq!(bitmap.glyphs.len(), 95 * bitmap.height as usize);
+            assert_eq!(bitmap.text_width("HELLO"), 5 * bitmap.advance());
+        }
+
+        assert_eq!(
+            IBM_FONT.glyph('A').unwrap(),
+            &[0x18, 0x24, 0x42, 0x42, 0x7E, 0x42, 0x42, 0x00]
+        );
+        assert_eq!(
+            PRO_FONT.glyph('g').unwrap(),
+            &[0x00, 0x00, 0x00, 0x00, 0x0E, 0x12, 0x12, 0x0E, 0x02, 0x0C]
+        );
+
+        assert_eq!(DEFAULT_FONT.unsupported_char("naïve"), Some('ï'));
+        assert_eq!(DEFAULT_FONT.unsupported_char("plain ASCII ~"), None);
+    }
+
+    #[test]
+    fn renders_glyph_pixels() {
+        let white = RgbColor::from_rgb(255, 255, 255);
+        let mut buffer = RgbBuffer::new(12, 8);
+        DEFAULT_FONT.render(&mut buffer, 1, 0, "|", white);
+
+        let lit: Vec<(u32, u32)> = (0..8)
+            .flat_map(|y| (0..12).map(move |x| (x, y)))
+            .filter(|(x, y)| buffer.get(*x, *y) == white)
+            .collect();
+        assert_eq!(lit, (0..6).map(|y| (3, y)).collect::<Vec<_>>());
+
+        //Partly off-screen text is clipped instead of wrapping around
+        let mut buffer = RgbBuffer::new(4, 4);
+        PRO_FONT.render(&mut buffer, -3, -2, "AB", white);
+        assert!(buffer.pixels().contains(&white));
+    }
+}
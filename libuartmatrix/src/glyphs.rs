@@ -0,0 +1,305 @@
+//Glyph tables for printable ASCII (' ' to '~'), rendered from the bitmaps shipped with
+//embedded-graphics (misc-fixed 5x7, public domain), the profont crate (ProFont, MIT) and
+//the ibm437 crate (IBM PC code page 437, dumped from the CGA character ROM, MIT). One byte
+//per glyph row, top row first, the leftmost pixel in the highest used bit. The tables
+//cover the whole character cell, so most glyphs leave their last column blank as spacing.
+
+//misc-fixed 5x7
+#[rustfmt::skip]
+pub(crate) static DEFAULT_GLYPHS: [u8; 95 * 7] = [
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, //' '
+    0x04, 0x04, 0x04, 0x04, 0x00, 0x04, 0x00, //'!'
+    0x0A, 0x0A, 0x0A, 0x00, 0x00, 0x00, 0x00, //'"'
+    0x00, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x00, //'#'
+    0x00, 0x0E, 0x14, 0x0E, 0x05, 0x0E, 0x00, //'$'
+    0x10, 0x12, 0x04, 0x08, 0x12, 0x02, 0x00, //'%'
+    0x00, 0x08, 0x14, 0x08, 0x14, 0x0A, 0x00, //'&'
+    0x04, 0x04, 0x04, 0x00, 0x00, 0x00, 0x00, //'\''
+    0x04, 0x08, 0x08, 0x08, 0x08, 0x04, 0x00, //'('
+    0x08, 0x04, 0x04, 0x04, 0x04, 0x08, 0x00, //')'
+    0x00, 0x0A, 0x04, 0x0E, 0x04, 0x0A, 0x00, //'*'
+    0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00, //'+'
+    0x00, 0x00, 0x00, 0x00, 0x06, 0x04, 0x08, //','
+    0x00, 0x00, 0x00, 0x1E, 0x00, 0x00, 0x00, //'-'
+    0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C, 0x00, //'.'
+    0x00, 0x02, 0x04, 0x08, 0x10, 0x00, 0x00, //'/'
+    0x04, 0x0A, 0x0A, 0x0A, 0x0A, 0x04, 0x00, //'0'
+    0x04, 0x0C, 0x04, 0x04, 0x04, 0x0E, 0x00, //'1'
+    0x0C, 0x12, 0x02, 0x04, 0x08, 0x1E, 0x00, //'2'
+    0x1E, 0x02, 0x0C, 0x02, 0x12, 0x0C, 0x00, //'3'
+    0x04, 0x0C, 0x14, 0x1E, 0x04, 0x04, 0x00, //'4'
+    0x1E, 0x10, 0x1C, 0x02, 0x12, 0x0C, 0x00, //'5'
+    0x0C, 0x10, 0x1C, 0x12, 0x12, 0x0C, 0x00, //'6'
+    0x1E, 0x02, 0x04, 0x04, 0x08, 0x08, 0x00, //'7'
+    0x0C, 0x12, 0x0C, 0x12, 0x12, 0x0C, 0x00, //'8'
+    0x0C, 0x12, 0x12, 0x0E, 0x02, 0x0C, 0x00, //'9'
+    0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00, //':'
+    0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x08, 0x10, //';'
+    0x00, 0x02, 0x04, 0x08, 0x04, 0x02, 0x00, //'<'
+    0x00, 0x00, 0x1E, 0x00, 0x1E, 0x00, 0x00, //'='
+    0x00, 0x08, 0x04, 0x02, 0x04, 0x08, 0x00, //'>'
+    0x04, 0x0A, 0x02, 0x04, 0x00, 0x04, 0x00, //'?'
+    0x0C, 0x12, 0x16, 0x16, 0x10, 0x0C, 0x00, //'@'
+    0x0C, 0x12, 0x12, 0x1E, 0x12, 0x12, 0x00, //'A'
+    0x1C, 0x12, 0x1C, 0x12, 0x12, 0x1C, 0x00, //'B'
+    0x0C, 0x12, 0x10, 0x10, 0x12, 0x0C, 0x00, //'C'
+    0x1C, 0x12, 0x12, 0x12, 0x12, 0x1C, 0x00, //'D'
+    0x1E, 0x10, 0x1C, 0x10, 0x10, 0x1E, 0x00, //'E'
+    0x1E, 0x10, 0x1C, 0x10, 0x10, 0x10, 0x00, //'F'
+    0x0C, 0x12, 0x10, 0x16, 0x12, 0x0E, 0x00, //'G'
+    0x12, 0x12, 0x1E, 0x12, 0x12, 0x12, 0x00, //'H'
+    0x0E, 0x04, 0x04, 0x04, 0x04, 0x0E, 0x00, //'I'
+    0x02, 0x02, 0x02, 0x02, 0x12, 0x0C, 0x00, //'J'
+    0x12, 0x14, 0x18, 0x18, 0x14, 0x12, 0x00, //'K'
+    0x10, 0x10, 0x10, 0x10, 0x10, 0x1E, 0x00, //'L'
+    0x12, 0x1E, 0x1E, 0x12, 0x12, 0x12, 0x00, //'M'
+    0x12, 0x1A, 0x1A, 0x16, 0x16, 0x12, 0x00, //'N'
+    0x0C, 0x12, 0x12, 0x12, 0x12, 0x0C, 0x00, //'O'
+    0x1C, 0x12, 0x12, 0x1C, 0x10, 0x10, 0x00, //'P'
+    0x0C, 0x12, 0x12, 0x12, 0x1A, 0x0C, 0x02, //'Q'
+    0x1C, 0x12, 0x12, 0x1C, 0x14, 0x12, 0x00, //'R'
+    0x0C, 0x12, 0x08, 0x04, 0x12, 0x0C, 0x00, //'S'
+    0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x00, //'T'
+    0x12, 0x12, 0x12, 0x12, 0x12, 0x0C, 0x00, //'U'
+    0x12, 0x12, 0x12, 0x12, 0x0C, 0x0C, 0x00, //'V'
+    0x12, 0x12, 0x12, 0x1E, 0x1E, 0x12, 0x00, //'W'
+    0x12, 0x12, 0x0C, 0x0C, 0x12, 0x12, 0x00, //'X'
+    0x0A, 0x0A, 0x0A, 0x04, 0x04, 0x04, 0x00, //'Y'
+    0x1E, 0x02, 0x04, 0x08, 0x10, 0x1E, 0x00, //'Z'
+    0x0E, 0x08, 0x08, 0x08, 0x08, 0x0E, 0x00, //'['
+    0x00, 0x10, 0x08, 0x04, 0x02, 0x00, 0x00, //'\\'
+    0x0E, 0x02, 0x02, 0x02, 0x02, 0x0E, 0x00, //']'
+    0x04, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, //'^'
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x1E, 0x00, //'_'
+    0x08, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, //'`'
+    0x00, 0x00, 0x0E, 0x12, 0x16, 0x0A, 0x00, //'a'
+    0x10, 0x10, 0x1C, 0x12, 0x12, 0x1C, 0x00, //'b'
+    0x00, 0x00, 0x0C, 0x10, 0x10, 0x0C, 0x00, //'c'
+    0x02, 0x02, 0x0E, 0x12, 0x12, 0x0E, 0x00, //'d'
+    0x00, 0x00, 0x0C, 0x16, 0x18, 0x0C, 0x00, //'e'
+    0x04, 0x0A, 0x08, 0x1C, 0x08, 0x08, 0x00, //'f'
+    0x00, 0x00, 0x0E, 0x12, 0x0C, 0x10, 0x0E, //'g'
+    0x10, 0x10, 0x1C, 0x12, 0x12, 0x12, 0x00, //'h'
+    0x04, 0x00, 0x0C, 0x04, 0x04, 0x0E, 0x00, //'i'
+    0x02, 0x00, 0x02, 0x02, 0x02, 0x0A, 0x04, //'j'
+    0x10, 0x10, 0x14, 0x18, 0x14, 0x12, 0x00, //'k'
+    0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E, 0x00, //'l'
+    0x00, 0x00, 0x14, 0x1E, 0x12, 0x12, 0x00, //'m'
+    0x00, 0x00, 0x1C, 0x12, 0x12, 0x12, 0x00, //'n'
+    0x00, 0x00, 0x0C, 0x12, 0x12, 0x0C, 0x00, //'o'
+    0x00, 0x00, 0x1C, 0x12, 0x12, 0x1C, 0x10, //'p'
+    0x00, 0x00, 0x0E, 0x12, 0x12, 0x0E, 0x02, //'q'
+    0x00, 0x00, 0x1C, 0x12, 0x10, 0x10, 0x00, //'r'
+    0x00, 0x00, 0x0E, 0x18, 0x06, 0x1C, 0x00, //'s'
+    0x08, 0x08, 0x1C, 0x08, 0x08, 0x06, 0x00, //'t'
+    0x00, 0x00, 0x12, 0x12, 0x12, 0x0E, 0x00, //'u'
+    0x00, 0x00, 0x0A, 0x0A, 0x0A, 0x04, 0x00, //'v'
+    0x00, 0x00, 0x12, 0x12, 0x1E, 0x1E, 0x00, //'w'
+    0x00, 0x00, 0x12, 0x0C, 0x0C, 0x12, 0x00, //'x'
+    0x00, 0x00, 0x12, 0x12, 0x0A, 0x04, 0x08, //'y'
+    0x00, 0x00, 0x1E, 0x04, 0x08, 0x1E, 0x00, //'z'
+    0x02, 0x04, 0x0C, 0x04, 0x04, 0x02, 0x00, //'{'
+    0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x00, //'|'
+    0x08, 0x04, 0x06, 0x04, 0x04, 0x08, 0x00, //'}'
+    0x0A, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, //'~'
+];
+
+//ProFont 7 point
+#[rustfmt::skip]
+pub(crate) static PRO_GLYPHS: [u8; 95 * 10] = [
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, //' '
+    0x00, 0x00, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04, 0x00, 0x00, //'!'
+    0x00, 0x0A, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, //'"'
+    0x00, 0x00, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x00, 0x00, 0x00, //'#'
+    0x00, 0x04, 0x0F, 0x14, 0x0E, 0x05, 0x15, 0x0E, 0x04, 0x00, //'$'
+    0x00, 0x00, 0x0F, 0x15, 0x16, 0x0D, 0x15, 0x12, 0x00, 0x00, //'%'
+    0x00, 0x00, 0x08, 0x14, 0x08, 0x0A, 0x14, 0x0A, 0x00, 0x00, //'&'
+    0x00, 0x00, 0x04, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, //'\''
+    0x00, 0x02, 0x04, 0x08, 0x08, 0x08, 0x08, 0x04, 0x02, 0x00, //'('
+    0x00, 0x10, 0x08, 0x04, 0x04, 0x04, 0x04, 0x08, 0x10, 0x00, //')'
+    0x00, 0x00, 0x08, 0x12, 0x0C, 0x12, 0x04, 0x00, 0x00, 0x00, //'*'
+    0x00, 0x00, 0x00, 0x00, 0x08, 0x1C, 0x08, 0x00, 0x00, 0x00, //'+'
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x08, 0x10, //','
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x00, 0x00, 0x00, //'-'
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, //'.'
+    0x00, 0x00, 0x02, 0x02, 0x04, 0x04, 0x08, 0x08, 0x10, 0x10, //'/'
+    0x00, 0x00, 0x0C, 0x12, 0x16, 0x1A, 0x12, 0x0C, 0x00, 0x00, //'0'
+    0x00, 0x00, 0x04, 0x0C, 0x04, 0x04, 0x04, 0x0E, 0x00, 0x00, //'1'
+    0x00, 0x00, 0x0C, 0x12, 0x02, 0x04, 0x08, 0x1E, 0x00, 0x00, //'2'
+    0x00, 0x00, 0x0C, 0x12, 0x04, 0x02, 0x12, 0x0C, 0x00, 0x00, //'3'
+    0x00, 0x00, 0x04, 0x0C, 0x14, 0x1E, 0x04, 0x0E, 0x00, 0x00, //'4'
+    0x00, 0x00, 0x1E, 0x10, 0x1C, 0x02, 0x12, 0x0C, 0x00, 0x00, //'5'
+    0x00, 0x00, 0x0C, 0x10, 0x1C, 0x12, 0x12, 0x0C, 0x00, 0x00, //'6'
+    0x00, 0x00, 0x1E, 0x02, 0x04, 0x08, 0x08, 0x08, 0x00, 0x00, //'7'
+    0x00, 0x00, 0x0C, 0x12, 0x0C, 0x12, 0x12, 0x0C, 0x00, 0x00, //'8'
+    0x00, 0x00, 0x0C, 0x12, 0x12, 0x0E, 0x02, 0x0C, 0x00, 0x00, //'9'
+    0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x04, 0x00, 0x00, //':'
+    0x00, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x08, 0x08, 0x10, //';'
+    0x00, 0x00, 0x00, 0x02, 0x04, 0x08, 0x04, 0x02, 0x00, 0x00, //'<'
+    0x00, 0x00, 0x00, 0x00, 0x1E, 0x00, 0x1E, 0x00, 0x00, 0x00, //'='
+    0x00, 0x00, 0x00, 0x08, 0x04, 0x02, 0x04, 0x08, 0x00, 0x00, //'>'
+    0x00, 0x00, 0x0C, 0x12, 0x04, 0x08, 0x00, 0x08, 0x00, 0x00, //'?'
+    0x00, 0x00, 0x0C, 0x12, 0x16, 0x16, 0x10, 0x0E, 0x00, 0x00, //'@'
+    0x00, 0x00, 0x0C, 0x12, 0x12, 0x1E, 0x12, 0x12, 0x00, 0x00, //'A'
+    0x00, 0x00, 0x1C, 0x12, 0x1C, 0x12, 0x12, 0x1C, 0x00, 0x00, //'B'
+    0x00, 0x00, 0x0C, 0x12, 0x10, 0x10, 0x12, 0x0C, 0x00, 0x00, //'C'
+    0x00, 0x00, 0x1C, 0x12, 0x12, 0x12, 0x12, 0x1C, 0x00, 0x00, //'D'
+    0x00, 0x00, 0x1E, 0x10, 0x1C, 0x10, 0x10, 0x1E, 0x00, 0x00, //'E'
+    0x00, 0x00, 0x1E, 0x10, 0x1C, 0x10, 0x10, 0x10, 0x00, 0x00, //'F'
+    0x00, 0x00, 0x0C, 0x12, 0x10, 0x16, 0x12, 0x0C, 0x00, 0x00, //'G'
+    0x00, 0x00, 0x12, 0x12, 0x1E, 0x12, 0x12, 0x12, 0x00, 0x00, //'H'
+    0x00, 0x00, 0x0E, 0x04, 0x04, 0x04, 0x04, 0x0E, 0x00, 0x00, //'I'
+    0x00, 0x00, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C, 0x00, 0x00, //'J'
+    0x00, 0x00, 0x12, 0x14, 0x18, 0x18, 0x14, 0x12, 0x00, 0x00, //'K'
+    0x00, 0x00, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1E, 0x00, 0x00, //'L'
+    0x00, 0x00, 0x12, 0x1E, 0x1E, 0x12, 0x12, 0x12, 0x00, 0x00, //'M'
+    0x00, 0x00, 0x12, 0x1A, 0x16, 0x12, 0x12, 0x12, 0x00, 0x00, //'N'
+    0x00, 0x00, 0x0C, 0x12, 0x12, 0x12, 0x12, 0x0C, 0x00, 0x00, //'O'
+    0x00, 0x00, 0x1C, 0x12, 0x12, 0x1C, 0x10, 0x10, 0x00, 0x00, //'P'
+    0x00, 0x00, 0x0C, 0x12, 0x12, 0x12, 0x16, 0x0C, 0x02, 0x00, //'Q'
+    0x00, 0x00, 0x1C, 0x12, 0x12, 0x1C, 0x12, 0x12, 0x00, 0x00, //'R'
+    0x00, 0x00, 0x0C, 0x12, 0x0C, 0x02, 0x12, 0x0C, 0x00, 0x00, //'S'
+    0x00, 0x00, 0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x00, //'T'
+    0x00, 0x00, 0x12, 0x12, 0x12, 0x12, 0x12, 0x0C, 0x00, 0x00, //'U'
+    0x00, 0x00, 0x12, 0x12, 0x12, 0x12, 0x14, 0x08, 0x00, 0x00, //'V'
+    0x00, 0x00, 0x12, 0x12, 0x12, 0x1E, 0x1E, 0x12, 0x00, 0x00, //'W'
+    0x00, 0x00, 0x12, 0x12, 0x0C, 0x0C, 0x12, 0x12, 0x00, 0x00, //'X'
+    0x00, 0x00, 0x12, 0x12, 0x14, 0x08, 0x08, 0x08, 0x00, 0x00, //'Y'
+    0x00, 0x00, 0x1E, 0x02, 0x04, 0x08, 0x10, 0x1E, 0x00, 0x00, //'Z'
+    0x00, 0x06, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x06, 0x00, //'['
+    0x00, 0x00, 0x08, 0x08, 0x04, 0x04, 0x02, 0x02, 0x01, 0x01, //'\\'
+    0x00, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0C, 0x00, //']'
+    0x00, 0x00, 0x04, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, //'^'
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F, //'_'
+    0x00, 0x08, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, //'`'
+    0x00, 0x00, 0x00, 0x00, 0x0E, 0x12, 0x16, 0x0A, 0x00, 0x00, //'a'
+    0x00, 0x00, 0x10, 0x10, 0x1C, 0x12, 0x12, 0x1C, 0x00, 0x00, //'b'
+    0x00, 0x00, 0x00, 0x00, 0x0C, 0x12, 0x10, 0x0E, 0x00, 0x00, //'c'
+    0x00, 0x00, 0x02, 0x02, 0x0E, 0x12, 0x12, 0x0E, 0x00, 0x00, //'d'
+    0x00, 0x00, 0x00, 0x00, 0x0C, 0x1E, 0x10, 0x0E, 0x00, 0x00, //'e'
+    0x00, 0x00, 0x02, 0x04, 0x0E, 0x04, 0x04, 0x04, 0x00, 0x00, //'f'
+    0x00, 0x00, 0x00, 0x00, 0x0E, 0x12, 0x12, 0x0E, 0x02, 0x0C, //'g'
+    0x00, 0x00, 0x10, 0x10, 0x1C, 0x12, 0x12, 0x12, 0x00, 0x00, //'h'
+    0x00, 0x00, 0x04, 0x00, 0x0C, 0x04, 0x04, 0x0E, 0x00, 0x00, //'i'
+    0x00, 0x00, 0x04, 0x00, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x08, //'j'
+    0x00, 0x00, 0x10, 0x10, 0x14, 0x18, 0x14, 0x12, 0x00, 0x00, //'k'
+    0x00, 0x00, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E, 0x00, 0x00, //'l'
+    0x00, 0x00, 0x00, 0x00, 0x1E, 0x15, 0x15, 0x15, 0x00, 0x00, //'m'
+    0x00, 0x00, 0x00, 0x00, 0x14, 0x1A, 0x12, 0x12, 0x00, 0x00, //'n'
+    0x00, 0x00, 0x00, 0x00, 0x0C, 0x12, 0x12, 0x0C, 0x00, 0x00, //'o'
+    0x00, 0x00, 0x00, 0x00, 0x1C, 0x12, 0x12, 0x1C, 0x10, 0x10, //'p'
+    0x00, 0x00, 0x00, 0x00, 0x0E, 0x12, 0x12, 0x0E, 0x02, 0x02, //'q'
+    0x00, 0x00, 0x00, 0x00, 0x14, 0x1A, 0x10, 0x10, 0x00, 0x00, //'r'
+    0x00, 0x00, 0x00, 0x00, 0x0E, 0x1C, 0x02, 0x1C, 0x00, 0x00, //'s'
+    0x00, 0x00, 0x04, 0x04, 0x0E, 0x04, 0x04, 0x02, 0x00, 0x00, //'t'
+    0x00, 0x00, 0x00, 0x00, 0x12, 0x12, 0x16, 0x0A, 0x00, 0x00, //'u'
+    0x00, 0x00, 0x00, 0x00, 0x12, 0x12, 0x14, 0x08, 0x00, 0x00, //'v'
+    0x00, 0x00, 0x00, 0x00, 0x15, 0x15, 0x15, 0x0A, 0x00, 0x00, //'w'
+    0x00, 0x00, 0x00, 0x00, 0x12, 0x0C, 0x0C, 0x12, 0x00, 0x00, //'x'
+    0x00, 0x00, 0x00, 0x00, 0x12, 0x12, 0x12, 0x0E, 0x02, 0x0C, //'y'
+    0x00, 0x00, 0x00, 0x00, 0x1E, 0x04, 0x08, 0x1E, 0x00, 0x00, //'z'
+    0x00, 0x02, 0x04, 0x04, 0x04, 0x08, 0x04, 0x04, 0x04, 0x02, //'{'
+    0x00, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x00, //'|'
+    0x00, 0x08, 0x04, 0x04, 0x04, 0x02, 0x04, 0x04, 0x04, 0x08, //'}'
+    0x00, 0x00, 0x00, 0x00, 0x0A, 0x14, 0x00, 0x00, 0x00, 0x00, //'~'
+];
+
+//IBM PC code page 437, 8x8
+#[rustfmt::skip]
+pub(crate) static IBM_GLYPHS: [u8; 95 * 8] = [
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, //' '
+    0x10, 0x38, 0x38, 0x10, 0x10, 0x00, 0x10, 0x00, //'!'
+    0x24, 0x24, 0x24, 0x00, 0x00, 0x00, 0x00, 0x00, //'"'
+    0x24, 0x24, 0x7E, 0x24, 0x7E, 0x24, 0x24, 0x00, //'#'
+    0x18, 0x3E, 0x40, 0x3C, 0x02, 0x7C, 0x18, 0x00, //'$'
+    0x00, 0x62, 0x64, 0x08, 0x10, 0x26, 0x46, 0x00, //'%'
+    0x30, 0x48, 0x30, 0x56, 0x88, 0x88, 0x76, 0x00, //'&'
+    0x10, 0x10, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00, //'\''
+    0x10, 0x20, 0x40, 0x40, 0x40, 0x20, 0x10, 0x00, //'('
+    0x20, 0x10, 0x08, 0x08, 0x08, 0x10, 0x20, 0x00, //')'
+    0x00, 0x44, 0x38, 0xFE, 0x38, 0x44, 0x00, 0x00, //'*'
+    0x00, 0x10, 0x10, 0x7C, 0x10, 0x10, 0x00, 0x00, //'+'
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x10, 0x20, //','
+    0x00, 0x00, 0x00, 0x7E, 0x00, 0x00, 0x00, 0x00, //'-'
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x10, 0x00, //'.'
+    0x00, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x00, //'/'
+    0x3C, 0x42, 0x46, 0x4A, 0x52, 0x62, 0x3C, 0x00, //'0'
+    0x10, 0x30, 0x50, 0x10, 0x10, 0x10, 0x7C, 0x00, //'1'
+    0x3C, 0x42, 0x02, 0x0C, 0x30, 0x42, 0x7E, 0x00, //'2'
+    0x3C, 0x42, 0x02, 0x1C, 0x02, 0x42, 0x3C, 0x00, //'3'
+    0x08, 0x18, 0x28, 0x48, 0xFE, 0x08, 0x1C, 0x00, //'4'
+    0x7E, 0x40, 0x7C, 0x02, 0x02, 0x42, 0x3C, 0x00, //'5'
+    0x1C, 0x20, 0x40, 0x7C, 0x42, 0x42, 0x3C, 0x00, //'6'
+    0x7E, 0x42, 0x04, 0x08, 0x10, 0x10, 0x10, 0x00, //'7'
+    0x3C, 0x42, 0x42, 0x3C, 0x42, 0x42, 0x3C, 0x00, //'8'
+    0x3C, 0x42, 0x42, 0x3E, 0x02, 0x04, 0x38, 0x00, //'9'
+    0x00, 0x10, 0x10, 0x00, 0x00, 0x10, 0x10, 0x00, //':'
+    0x00, 0x10, 0x10, 0x00, 0x00, 0x10, 0x10, 0x20, //';'
+    0x08, 0x10, 0x20, 0x40, 0x20, 0x10, 0x08, 0x00, //'<'
+    0x00, 0x00, 0x7E, 0x00, 0x00, 0x7E, 0x00, 0x00, //'='
+    0x10, 0x08, 0x04, 0x02, 0x04, 0x08, 0x10, 0x00, //'>'
+    0x3C, 0x42, 0x02, 0x04, 0x08, 0x00, 0x08, 0x00, //'?'
+    0x3C, 0x42, 0x5E, 0x52, 0x5E, 0x40, 0x3C, 0x00, //'@'
+    0x18, 0x24, 0x42, 0x42, 0x7E, 0x42, 0x42, 0x00, //'A'
+    0x7C, 0x22, 0x22, 0x3C, 0x22, 0x22, 0x7C, 0x00, //'B'
+    0x1C, 0x22, 0x40, 0x40, 0x40, 0x22, 0x1C, 0x00, //'C'
+    0x78, 0x24, 0x22, 0x22, 0x22, 0x24, 0x78, 0x00, //'D'
+    0x7E, 0x22, 0x28, 0x38, 0x28, 0x22, 0x7E, 0x00, //'E'
+    0x7E, 0x22, 0x28, 0x38, 0x28, 0x20, 0x70, 0x00, //'F'
+    0x1C, 0x22, 0x40, 0x40, 0x4E, 0x22, 0x1E, 0x00, //'G'
+    0x42, 0x42, 0x42, 0x7E, 0x42, 0x42, 0x42, 0x00, //'H'
+    0x38, 0x10, 0x10, 0x10, 0x10, 0x10, 0x38, 0x00, //'I'
+    0x0E, 0x04, 0x04, 0x04, 0x44, 0x44, 0x38, 0x00, //'J'
+    0x62, 0x24, 0x28, 0x30, 0x28, 0x24, 0x63, 0x00, //'K'
+    0x70, 0x20, 0x20, 0x20, 0x20, 0x22, 0x7E, 0x00, //'L'
+    0x63, 0x55, 0x49, 0x41, 0x41, 0x41, 0x41, 0x00, //'M'
+    0x62, 0x52, 0x4A, 0x46, 0x42, 0x42, 0x42, 0x00, //'N'
+    0x18, 0x24, 0x42, 0x42, 0x42, 0x24, 0x18, 0x00, //'O'
+    0x7C, 0x22, 0x22, 0x3C, 0x20, 0x20, 0x70, 0x00, //'P'
+    0x3C, 0x42, 0x42, 0x42, 0x4A, 0x3C, 0x03, 0x00, //'Q'
+    0x7C, 0x22, 0x22, 0x3C, 0x28, 0x24, 0x72, 0x00, //'R'
+    0x3C, 0x42, 0x40, 0x3C, 0x02, 0x42, 0x3C, 0x00, //'S'
+    0x7F, 0x49, 0x08, 0x08, 0x08, 0x08, 0x1C, 0x00, //'T'
+    0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x3C, 0x00, //'U'
+    0x41, 0x41, 0x41, 0x41, 0x22, 0x14, 0x08, 0x00, //'V'
+    0x41, 0x41, 0x41, 0x49, 0x49, 0x49, 0x36, 0x00, //'W'
+    0x41, 0x22, 0x14, 0x08, 0x14, 0x22, 0x41, 0x00, //'X'
+    0x41, 0x22, 0x14, 0x08, 0x08, 0x08, 0x1C, 0x00, //'Y'
+    0x7F, 0x42, 0x04, 0x08, 0x10, 0x21, 0x7F, 0x00, //'Z'
+    0x78, 0x40, 0x40, 0x40, 0x40, 0x40, 0x78, 0x00, //'['
+    0x80, 0x40, 0x20, 0x10, 0x08, 0x04, 0x02, 0x00, //'\\'
+    0x78, 0x08, 0x08, 0x08, 0x08, 0x08, 0x78, 0x00, //']'
+    0x10, 0x28, 0x44, 0x82, 0x00, 0x00, 0x00, 0x00, //'^'
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, //'_'
+    0x10, 0x10, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, //'`'
+    0x00, 0x00, 0x3C, 0x02, 0x3E, 0x42, 0x3F, 0x00, //'a'
+    0x60, 0x20, 0x20, 0x2E, 0x31, 0x31, 0x2E, 0x00, //'b'
+    0x00, 0x00, 0x3C, 0x42, 0x40, 0x42, 0x3C, 0x00, //'c'
+    0x06, 0x02, 0x02, 0x3A, 0x46, 0x46, 0x3B, 0x00, //'d'
+    0x00, 0x00, 0x3C, 0x42, 0x7E, 0x40, 0x3C, 0x00, //'e'
+    0x0C, 0x12, 0x10, 0x38, 0x10, 0x10, 0x38, 0x00, //'f'
+    0x00, 0x00, 0x3D, 0x42, 0x42, 0x3E, 0x02, 0x7C, //'g'
+    0x60, 0x20, 0x2C, 0x32, 0x22, 0x22, 0x62, 0x00, //'h'
+    0x10, 0x00, 0x30, 0x10, 0x10, 0x10, 0x38, 0x00, //'i'
+    0x02, 0x00, 0x06, 0x02, 0x02, 0x42, 0x42, 0x3C, //'j'
+    0x60, 0x20, 0x24, 0x28, 0x30, 0x28, 0x26, 0x00, //'k'
+    0x30, 0x10, 0x10, 0x10, 0x10, 0x10, 0x38, 0x00, //'l'
+    0x00, 0x00, 0x76, 0x49, 0x49, 0x49, 0x49, 0x00, //'m'
+    0x00, 0x00, 0x5C, 0x62, 0x42, 0x42, 0x42, 0x00, //'n'
+    0x00, 0x00, 0x3C, 0x42, 0x42, 0x42, 0x3C, 0x00, //'o'
+    0x00, 0x00, 0x6C, 0x32, 0x32, 0x2C, 0x20, 0x70, //'p'
+    0x00, 0x00, 0x36, 0x4C, 0x4C, 0x34, 0x04, 0x0E, //'q'
+    0x00, 0x00, 0x6C, 0x32, 0x22, 0x20, 0x70, 0x00, //'r'
+    0x00, 0x00, 0x3E, 0x40, 0x3C, 0x02, 0x7C, 0x00, //'s'
+    0x10, 0x10, 0x7C, 0x10, 0x10, 0x12, 0x0C, 0x00, //'t'
+    0x00, 0x00, 0x42, 0x42, 0x42, 0x46, 0x3A, 0x00, //'u'
+    0x00, 0x00, 0x41, 0x41, 0x22, 0x14, 0x08, 0x00, //'v'
+    0x00, 0x00, 0x41, 0x49, 0x49, 0x49, 0x36, 0x00, //'w'
+    0x00, 0x00, 0x44, 0x28, 0x10, 0x28, 0x44, 0x00, //'x'
+    0x00, 0x00, 0x42, 0x42, 0x42, 0x3E, 0x02, 0x7C, //'y'
+    0x00, 0x00, 0x7C, 0x08, 0x10, 0x20, 0x7C, 0x00, //'z'
+    0x0C, 0x10, 0x10, 0x60, 0x10, 0x10, 0x0C, 0x00, //'{'
+    0x10, 0x10, 0x10, 0x00, 0x10, 0x10, 0x10, 0x00, //'|'
+    0x30, 0x08, 0x08, 0x06, 0x08, 0x08, 0x30, 0x00, //'}'
+    0x32, 0x4C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, //'~'
+];
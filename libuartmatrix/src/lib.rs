@@ -1,20 +1,66 @@
-use crate::enums::{Animation, Direction, DisplayMode};
-
-use std::os::raw::*;
-
-pub mod enums;
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+
+use crate::enums::{Animation, Direction, DisplayMode, FontType};
+
+use core::convert::TryFrom;
+use core::ffi::{c_int, c_uchar, c_uint};
+use core::fmt;
+use core::ops::Deref;
+
+#[cfg(feature = "tokio-serial")]
+pub use async_client::open_serial;
+#[cfg(feature = "tokio")]
+pub use async_client::AsyncMatrixClient;
+#[cfg(feature = "std")]
+pub use client::{AckMode, ClientError, MatrixClient, Pacing};
+pub use command::Command;
+pub use decoder::FrameDecoder;
+pub use error::*;
+pub use font::BitmapFont;
+pub use params::DeviceParams;
+pub use response::{NackReason, Response};
+#[cfg(feature = "std")]
+pub use transport::Transport;
+
+#[cfg(feature = "tokio")]
+mod async_client;
+#[cfg(feature = "std")]
+mod client;
+mod command;
 mod crc;
+mod decoder;
+pub mod enums;
+mod error;
+pub mod font;
+mod glyphs;
+mod params;
+pub mod raster;
+mod response;
+#[cfg(feature = "std")]
+mod transport;
+mod writer;
 
 pub const MAX_FRAME_SIZE: usize = 512;
 pub const MAX_TEXT_LENGTH: usize = 255;
 
+//Version 1 had output control and ping colliding with the draw commands
+pub const PROTOCOL_VERSION: u8 = 2;
+
+pub const FRAME_HEADER: [u8; 3] = [b'U', b'M', b'X'];
+//Header, two length bytes and the CRC trailer
+pub const FRAME_OVERHEAD: usize = 6;
+pub const MAX_PAYLOAD_SIZE: usize = MAX_FRAME_SIZE - FRAME_OVERHEAD;
+//Opcode and row number leave room for this many pixels in a single DrawRow
+pub const MAX_ROW_PIXELS: usize = (MAX_PAYLOAD_SIZE - 2) / 3;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[repr(C)]
 pub struct Point {
     pub x: u8,
     pub y: u8,
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[repr(C)]
 pub struct RgbColor {
     pub r: u8,
@@ -30,68 +76,167 @@ impl RgbColor {
             b: 128,
         }
     }
+
+    pub fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+        RgbColor { r, g, b }
+    }
+}
+
+/// A serialized UMX frame. Dereferences to exactly the bytes that go on the wire.
+#[derive(Clone)]
+pub struct Frame {
+    buffer: [u8; MAX_FRAME_SIZE],
+    len: usize,
+}
+
+impl Frame {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer[..self.len]
+    }
+}
+
+impl Deref for Frame {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl AsRef<[u8]> for Frame {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl fmt::Debug for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Frame").field(&self.as_bytes()).finish()
+    }
 }
 
-fn serialize_umx_frame(content: &[u8]) -> Option<[u8; MAX_FRAME_SIZE]> {
-    if content.len() > MAX_FRAME_SIZE {
-        return None;
+/// Writes a complete frame into `buffer`, letting `write_payload` fill in the payload.
+fn serialize_frame_with<F>(buffer: &mut [u8], write_payload: F) -> Result<usize, UmxError>
+where
+    F: FnOnce(&mut [u8]) -> Result<usize, UmxError>,
+{
+    if buffer.len() < FRAME_OVERHEAD {
+        return Err(UmxError::BufferTooSmall {
+            needed: FRAME_OVERHEAD,
+            available: buffer.len(),
+        });
     }
-    let mut buffer = [0; MAX_FRAME_SIZE];
-    buffer[0] = b'U';
-    buffer[1] = b'M';
-    buffer[2] = b'X';
-    let length = content.len() as u16;
+
+    let available = buffer.len();
+    let payload_space = (available - FRAME_OVERHEAD).min(MAX_PAYLOAD_SIZE);
+    let length = write_payload(&mut buffer[5..(payload_space + 5)]).map_err(|e| match e {
+        UmxError::BufferTooSmall { needed, .. } if needed > MAX_PAYLOAD_SIZE => {
+            UmxError::FrameTooLarge {
+                length: needed,
+                max: MAX_PAYLOAD_SIZE,
+            }
+        }
+        UmxError::BufferTooSmall { needed, .. } => UmxError::BufferTooSmall {
+            needed: needed + FRAME_OVERHEAD,
+            available,
+        },
+        e => e,
+    })?;
+
+    buffer[0..3].clone_from_slice(&FRAME_HEADER);
     buffer[3] = (length >> 8) as u8;
     buffer[4] = length as u8;
+    buffer[length + 5] = crc::crc8_ccitt(&buffer[5..(length + 5)]);
 
-    buffer[5..(content.len() + 5)].clone_from_slice(&content);
-    buffer[content.len() + 5] = crc::crc8_ccitt(&buffer[5..(content.len() + 5)]);
+    Ok(length + FRAME_OVERHEAD)
+}
 
-    Some(buffer)
+/// Frames an already encoded payload into `buffer` and returns the frame length.
+pub fn serialize_frame_into(payload: &[u8], buffer: &mut [u8]) -> Result<usize, UmxError> {
+    serialize_frame_with(buffer, |space| {
+        if payload.len() > space.len() {
+            return Err(UmxError::BufferTooSmall {
+                needed: payload.len(),
+                available: space.len(),
+            });
+        }
+
+        space[..payload.len()].clone_from_slice(payload);
+        Ok(payload.len())
+    })
 }
 
-pub fn serialize_param_request() -> Option<[u8; MAX_FRAME_SIZE]> {
-    serialize_umx_frame(&[0])
+pub fn serialize_command_into(command: &Command, buffer: &mut [u8]) -> Result<usize, UmxError> {
+    serialize_frame_with(buffer, |space| command.encode_into(space))
 }
 
-pub fn serialize_switch_mode(mode: DisplayMode) -> Option<[u8; MAX_FRAME_SIZE]> {
-    serialize_umx_frame(&[1, mode.into()])
+pub fn serialize_response_into(response: &Response, buffer: &mut [u8]) -> Result<usize, UmxError> {
+    serialize_frame_with(buffer, |space| response.encode_into(space))
 }
 
-pub fn serialize_write_line(row: u8, text: &str) -> Option<[u8; MAX_FRAME_SIZE]> {
-    if text.len() > MAX_TEXT_LENGTH {
-        return None;
-    }
+fn serialize_to_frame<F>(serialize_into: F) -> Result<Frame, UmxError>
+where
+    F: FnOnce(&mut [u8]) -> Result<usize, UmxError>,
+{
+    let mut frame = Frame {
+        buffer: [0; MAX_FRAME_SIZE],
+        len: 0,
+    };
+    frame.len = serialize_into(&mut frame.buffer)?;
 
-    let data = [&[2], &[row], text.as_bytes()].concat();
+    Ok(frame)
+}
+
+/// Frames an already encoded payload.
+pub fn serialize_umx_frame(content: &[u8]) -> Result<Frame, UmxError> {
+    serialize_to_frame(|buffer| serialize_frame_into(content, buffer))
+}
+
+pub fn serialize_command(command: &Command) -> Result<Frame, UmxError> {
+    serialize_to_frame(|buffer| serialize_command_into(command, buffer))
+}
 
-    serialize_umx_frame(data.as_slice())
+pub fn serialize_response(response: &Response) -> Result<Frame, UmxError> {
+    serialize_to_frame(|buffer| serialize_response_into(response, buffer))
 }
 
-pub fn serialize_set_font(row: u8, font: u8) -> Option<[u8; MAX_FRAME_SIZE]> {
-    let data = [3, row, font];
-    serialize_umx_frame(&data)
+pub fn serialize_param_request() -> Result<Frame, UmxError> {
+    serialize_command(&Command::ParamRequest)
+}
+
+pub fn serialize_switch_mode(mode: DisplayMode) -> Result<Frame, UmxError> {
+    serialize_command(&Command::SwitchMode(mode))
+}
+
+pub fn serialize_write_line(row: u8, text: &str) -> Result<Frame, UmxError> {
+    if text.len() > MAX_TEXT_LENGTH {
+        return Err(UmxError::TextTooLong {
+            length: text.len(),
+            max: MAX_TEXT_LENGTH,
+        });
+    }
+
+    serialize_command(&Command::WriteLine {
+        row,
+        text: heapless::String::from(text),
+    })
 }
 
-pub fn serialize_set_color(row: u8, color: RgbColor) -> Option<[u8; MAX_FRAME_SIZE]> {
-    let RgbColor { r, g, b } = color;
-    let data = [4, row, r, g, b];
-    serialize_umx_frame(&data)
+pub fn serialize_set_font(row: u8, font: u8) -> Result<Frame, UmxError> {
+    let font = FontType::try_from(font).map_err(UmxError::InvalidFont)?;
+    serialize_command(&Command::SetFont { row, font })
 }
 
-pub fn serialize_set_animation(row: u8, animation: Animation) -> Option<[u8; MAX_FRAME_SIZE]> {
-    let mut anim_data: Vec<u8> = animation.into();
-    let mut data = vec![5, row];
-    data.append(&mut anim_data);
+pub fn serialize_set_color(row: u8, color: RgbColor) -> Result<Frame, UmxError> {
+    serialize_command(&Command::SetColor { row, color })
+}
 
-    serialize_umx_frame(data.as_slice())
+pub fn serialize_set_animation(row: u8, animation: Animation) -> Result<Frame, UmxError> {
+    serialize_command(&Command::SetAnimation { row, animation })
 }
 
-pub fn serialize_draw_pixel(position: Point, color: RgbColor) -> Option<[u8; MAX_FRAME_SIZE]> {
-    let Point { x, y } = position;
-    let RgbColor { r, g, b } = color;
-    let data = [6, x, y, r, g, b];
-    serialize_umx_frame(&data)
+pub fn serialize_draw_pixel(position: Point, color: RgbColor) -> Result<Frame, UmxError> {
+    serialize_command(&Command::DrawPixel { position, color })
 }
 
 pub fn serialize_draw_line(
@@ -99,14 +244,13 @@ pub fn serialize_draw_line(
     point_2: Point,
     thickness: u8,
     color: RgbColor,
-) -> Option<[u8; MAX_FRAME_SIZE]> {
-    let Point { x: x_1, y: y_1 } = point_1;
-    let Point { x: x_2, y: y_2 } = point_2;
-    let RgbColor { r, g, b } = color;
-
-    let data = [8, x_1, y_1, x_2, y_2, thickness, r, g, b];
-
-    serialize_umx_frame(&data)
+) -> Result<Frame, UmxError> {
+    serialize_command(&Command::DrawLine {
+        point_1,
+        point_2,
+        thickness,
+        color,
+    })
 }
 
 pub fn serialize_draw_rectangle(
@@ -115,16 +259,14 @@ pub fn serialize_draw_rectangle(
     thickness: u8,
     color: RgbColor,
     filled: bool,
-) -> Option<[u8; MAX_FRAME_SIZE]> {
-    let Point { x: x_1, y: y_1 } = point_1;
-    let Point { x: x_2, y: y_2 } = point_2;
-    let RgbColor { r, g, b } = color;
-
-    let filled = if filled { 1 } else { 0 };
-
-    let data = [9, x_1, y_1, x_2, y_2, thickness, r, g, b, filled];
-
-    serialize_umx_frame(&data)
+) -> Result<Frame, UmxError> {
+    serialize_command(&Command::DrawRectangle {
+        point_1,
+        point_2,
+        thickness,
+        color,
+        filled,
+    })
 }
 
 pub fn serialize_draw_triangle(
@@ -134,17 +276,15 @@ pub fn serialize_draw_triangle(
     thickness: u8,
     color: RgbColor,
     filled: bool,
-) -> Option<[u8; MAX_FRAME_SIZE]> {
-    let Point { x: x_1, y: y_1 } = point_1;
-    let Point { x: x_2, y: y_2 } = point_2;
-    let Point { x: x_3, y: y_3 } = point_3;
-    let RgbColor { r, g, b } = color;
-
-    let filled = if filled { 1 } else { 0 };
-
-    let data = [10, x_1, y_1, x_2, y_2, x_3, y_3, thickness, r, g, b, filled];
-
-    serialize_umx_frame(&data)
+) -> Result<Frame, UmxError> {
+    serialize_command(&Command::DrawTriangle {
+        point_1,
+        point_2,
+        point_3,
+        thickness,
+        color,
+        filled,
+    })
 }
 
 pub fn serialize_draw_circle(
@@ -153,62 +293,79 @@ pub fn serialize_draw_circle(
     thickness: u8,
     color: RgbColor,
     filled: bool,
-) -> Option<[u8; MAX_FRAME_SIZE]> {
-    let Point { x, y } = center;
-    let RgbColor { r, g, b } = color;
-
-    let filled = if filled { 1 } else { 0 };
-
-    let data = [11, x, y, radius, thickness, r, g, b, filled];
-
-    serialize_umx_frame(&data)
-}
-
-pub fn serialize_draw_row(row: u8, pixels: Vec<(u8, u8, u8)>) -> Option<[u8; MAX_FRAME_SIZE]> {
-    let mut data = vec![7, row];
-    for pixel in pixels {
-        data.push(pixel.0);
-        data.push(pixel.1);
-        data.push(pixel.2);
+) -> Result<Frame, UmxError> {
+    serialize_command(&Command::DrawCircle {
+        center,
+        radius,
+        thickness,
+        color,
+        filled,
+    })
+}
+
+pub fn serialize_draw_row<I>(row: u8, pixels: I) -> Result<Frame, UmxError>
+where
+    I: IntoIterator<Item = (u8, u8, u8)>,
+{
+    let mut pixels = pixels.into_iter();
+    let mut row_pixels = heapless::Vec::new();
+
+    for (r, g, b) in &mut pixels {
+        if row_pixels.push(RgbColor::from_rgb(r, g, b)).is_err() {
+            let count = MAX_ROW_PIXELS + 1 + pixels.count();
+            return Err(UmxError::FrameTooLarge {
+                length: 2 + count * 3,
+                max: MAX_PAYLOAD_SIZE,
+            });
+        }
     }
 
-    serialize_umx_frame(data.as_slice())
+    serialize_command(&Command::DrawRow {
+        row,
+        pixels: row_pixels,
+    })
 }
 
-pub fn serialize_clear() -> Option<[u8; MAX_FRAME_SIZE]> {
-    serialize_umx_frame(&[12])
+pub fn serialize_clear() -> Result<Frame, UmxError> {
+    serialize_command(&Command::Clear)
 }
 
-pub fn serialize_enable_output() -> Option<[u8; MAX_FRAME_SIZE]> {
-    serialize_umx_frame(&[9])
+pub fn serialize_enable_output() -> Result<Frame, UmxError> {
+    serialize_command(&Command::EnableOutput)
 }
 
-pub fn serialize_disable_output() -> Option<[u8; MAX_FRAME_SIZE]> {
-    serialize_umx_frame(&[10])
+pub fn serialize_disable_output() -> Result<Frame, UmxError> {
+    serialize_command(&Command::DisableOutput)
 }
 
-pub fn serialize_ping() -> Option<[u8; MAX_FRAME_SIZE]> {
-    serialize_umx_frame(&[11])
+pub fn serialize_ping() -> Result<Frame, UmxError> {
+    serialize_command(&Command::Ping {
+        version: PROTOCOL_VERSION,
+    })
 }
 
 //FFI interfaces
 
-fn opt_array_to_ffi(buffer: *mut c_uchar, array: Option<[u8; MAX_FRAME_SIZE]>) -> c_int {
-    match array {
-        Some(array) => {
+fn frame_to_ffi(buffer: *mut c_uchar, frame: Result<Frame, UmxError>) -> c_int {
+    if buffer.is_null() {
+        return UmxError::NullPointer.code();
+    }
+
+    match frame {
+        Ok(frame) => {
             unsafe {
-                std::ptr::copy(array.as_ptr(), buffer as *mut u8, array.len());
+                core::ptr::copy(frame.as_ptr(), buffer as *mut u8, frame.len());
             }
-            return array.len() as c_int;
+            return frame.len() as c_int;
         }
-        None => return -1 as c_int,
+        Err(e) => return e.code(),
     }
 }
 
 #[no_mangle]
 pub extern "C" fn umx_serialize_param_request(buffer: *mut c_uchar) -> c_int {
     let result = serialize_param_request();
-    return opt_array_to_ffi(buffer, result);
+    return frame_to_ffi(buffer, result);
 }
 
 #[no_mangle]
@@ -219,7 +376,7 @@ pub extern "C" fn umx_serialize_switch_mode(buffer: *mut c_uchar, mode: c_int) -
     };
 
     let result = serialize_switch_mode(mode);
-    return opt_array_to_ffi(buffer, result);
+    return frame_to_ffi(buffer, result);
 }
 
 #[no_mangle]
@@ -229,16 +386,20 @@ pub extern "C" fn umx_serialize_write_line(
     text: *const c_uchar,
     text_len: c_uint,
 ) -> c_int {
+    if text.is_null() {
+        return UmxError::NullPointer.code();
+    }
+
     unsafe {
         let slice = core::slice::from_raw_parts(text, text_len as usize);
         let string = core::str::from_utf8(slice);
         match string {
             Ok(string) => {
                 let result = serialize_write_line(row as u8, string);
-                return opt_array_to_ffi(buffer, result);
+                return frame_to_ffi(buffer, result);
             }
             Err(_e) => {
-                return -1;
+                return UmxError::InvalidUtf8.code();
             }
         }
     }
@@ -251,7 +412,7 @@ pub extern "C" fn umx_serialize_set_font(
     font: c_uchar,
 ) -> c_int {
     let result = serialize_set_font(row, font);
-    return opt_array_to_ffi(buffer, result);
+    return frame_to_ffi(buffer, result);
 }
 
 #[no_mangle]
@@ -261,7 +422,7 @@ pub extern "C" fn umx_serialize_set_color(
     color: RgbColor,
 ) -> c_int {
     let result = serialize_set_color(row, color);
-    return opt_array_to_ffi(buffer, result);
+    return frame_to_ffi(buffer, result);
 }
 
 #[no_mangle]
@@ -284,7 +445,7 @@ pub extern "C" fn umx_serialize_set_animation(
         _ => Animation::NoAnimation,
     };
     let result = serialize_set_animation(row, animation);
-    return opt_array_to_ffi(buffer, result);
+    return frame_to_ffi(buffer, result);
 }
 
 #[no_mangle]
@@ -294,7 +455,7 @@ pub extern "C" fn umx_serialize_draw_pixel(
     color: RgbColor,
 ) -> c_int {
     let result = serialize_draw_pixel(position, color);
-    return opt_array_to_ffi(buffer, result);
+    return frame_to_ffi(buffer, result);
 }
 
 #[no_mangle]
@@ -311,7 +472,7 @@ pub extern "C" fn umx_serialize_draw_rectangle(
         _ => true,
     };
     let result = serialize_draw_rectangle(point_1, point_2, thickness as u8, color, filled);
-    return opt_array_to_ffi(buffer, result);
+    return frame_to_ffi(buffer, result);
 }
 
 #[no_mangle]
@@ -329,7 +490,7 @@ pub extern "C" fn umx_serialize_draw_triangle(
         _ => true,
     };
     let result = serialize_draw_triangle(point_1, point_2, point_3, thickness as u8, color, filled);
-    return opt_array_to_ffi(buffer, result);
+    return frame_to_ffi(buffer, result);
 }
 
 #[no_mangle]
@@ -346,7 +507,7 @@ pub extern "C" fn umx_serialize_draw_circle(
         _ => true,
     };
     let result = serialize_draw_circle(center, radius as u8, thickness as u8, color, filled);
-    return opt_array_to_ffi(buffer, result);
+    return frame_to_ffi(buffer, result);
 }
 
 #[no_mangle]
@@ -355,41 +516,44 @@ pub extern "C" fn umx_serialize_draw_row(
     row: c_uchar,
     pixels: *const RgbColor,
     pixels_len: c_uint,
-) -> c_int{
+) -> c_int {
+    if pixels.is_null() {
+        return UmxError::NullPointer.code();
+    }
+
     unsafe {
-        let mut vec = vec![];
         let slice = core::slice::from_raw_parts(pixels, pixels_len as usize);
-        slice
+        let pixels = slice
             .iter()
-            .for_each(|element| vec.push((element.r, element.g, element.b)));
+            .map(|element| (element.r, element.g, element.b));
 
-        let result = serialize_draw_row(row, vec);
-        return opt_array_to_ffi(buffer, result);
+        let result = serialize_draw_row(row, pixels);
+        return frame_to_ffi(buffer, result);
     }
 }
 
 #[no_mangle]
 pub extern "C" fn umx_serialize_clear(buffer: *mut c_uchar) -> c_int {
     let result = serialize_clear();
-    return opt_array_to_ffi(buffer, result);
+    return frame_to_ffi(buffer, result);
 }
 
 #[no_mangle]
 pub extern "C" fn umx_serialize_enable_output(buffer: *mut c_uchar) -> c_int {
     let result = serialize_enable_output();
-    return opt_array_to_ffi(buffer, result);
+    return frame_to_ffi(buffer, result);
 }
 
 #[no_mangle]
 pub extern "C" fn umx_serialize_disable_output(buffer: *mut c_uchar) -> c_int {
     let result = serialize_disable_output();
-    return opt_array_to_ffi(buffer, result);
+    return frame_to_ffi(buffer, result);
 }
 
 #[no_mangle]
 pub extern "C" fn umx_serialize_ping(buffer: *mut c_uchar) -> c_int {
     let result = serialize_ping();
-    return opt_array_to_ffi(buffer, result);
+    return frame_to_ffi(buffer, result);
 }
 
 #[cfg(test)]
@@ -405,8 +569,97 @@ mod tests {
         assert_eq!(frame[0], 85);
         assert_eq!(frame[1], 77);
         assert_eq!(frame[2], 88);
-        assert_eq!(frame[3], 1);
-        assert_eq!(frame[4], 27);
+        assert_eq!(frame[3], 0);
+        assert_eq!(frame[4], 1);
+        assert_eq!(frame[5], 27);
+    }
+
+    #[test]
+    fn serialize_umx_rejects_oversized_payload() {
+        assert!(serialize_umx_frame(&[0; MAX_PAYLOAD_SIZE]).is_ok());
+        assert_eq!(
+            serialize_umx_frame(&[0; MAX_PAYLOAD_SIZE + 1]).unwrap_err(),
+            UmxError::FrameTooLarge {
+                length: MAX_PAYLOAD_SIZE + 1,
+                max: MAX_PAYLOAD_SIZE
+            }
+        );
+    }
+
+    #[test]
+    fn serialize_umx_has_no_padding() {
+        let frame = serialize_umx_frame(&[27, 28]).unwrap();
+        assert_eq!(frame.len(), 2 + FRAME_OVERHEAD);
+        assert_eq!(frame[7], crc::crc8_ccitt(&[27, 28]));
+
+        let frame = serialize_clear().unwrap();
+        assert_eq!(frame.as_bytes(), &[b'U', b'M', b'X', 0, 1, 12, 0x24]);
+    }
+
+    #[test]
+    fn serializers_report_distinct_errors() {
+        let long_text = "X".repeat(MAX_TEXT_LENGTH + 1);
+        assert_eq!(
+            serialize_write_line(0, &long_text).unwrap_err(),
+            UmxError::TextTooLong {
+                length: MAX_TEXT_LENGTH + 1,
+                max: MAX_TEXT_LENGTH
+            }
+        );
+        assert_eq!(
+            serialize_set_font(0, 9).unwrap_err(),
+            UmxError::InvalidFont(9)
+        );
+        assert!(matches!(
+            serialize_draw_row(0, vec![(0, 0, 0); 200]),
+            Err(UmxError::FrameTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn ffi_returns_error_codes() {
+        let mut buffer = [0; MAX_FRAME_SIZE];
+        let text = [0xFF_u8, 0xFE];
+
+        assert_eq!(
+            umx_serialize_set_font(buffer.as_mut_ptr(), 0, 9),
+            UMX_ERR_INVALID_FONT
+        );
+        assert_eq!(
+            umx_serialize_write_line(buffer.as_mut_ptr(), 0, text.as_ptr(), 2),
+            UMX_ERR_INVALID_UTF8
+        );
+        assert_eq!(
+            umx_serialize_clear(std::ptr::null_mut()),
+            UMX_ERR_NULL_POINTER
+        );
+        assert_eq!(umx_serialize_clear(buffer.as_mut_ptr()), 7);
+    }
+
+    #[test]
+    fn serialize_into_caller_buffer() {
+        let mut buffer = [0; 16];
+        let length = serialize_command_into(&Command::Clear, &mut buffer).unwrap();
+        assert_eq!(&buffer[..length], serialize_clear().unwrap().as_bytes());
+
+        let mut small = [0; 8];
+        let command = Command::SetColor {
+            row: 0,
+            color: RgbColor::new(),
+        };
+        assert_eq!(
+            serialize_command_into(&command, &mut small),
+            Err(UmxError::BufferTooSmall {
+                needed: 11,
+                available: 8
+            })
+        );
+
+        let length = serialize_frame_into(&[1, 2, 3], &mut buffer).unwrap();
+        let mut decoder = FrameDecoder::new();
+        let mut payloads = vec![];
+        decoder.feed(&buffer[..length], |payload| payloads.push(payload.to_vec()));
+        assert_eq!(payloads, vec![vec![1, 2, 3]]);
     }
 
     #[test]
@@ -414,6 +667,6 @@ mod tests {
         let frame = serialize_write_line(3, "THISISATEST").unwrap();
         assert_eq!(frame[5], 2);
         assert_eq!(frame[6], 3);
-        assert_eq!(&from_utf8(&frame[7..13]).unwrap()[0..12], "THISISATEST");
+        assert_eq!(from_utf8(&frame[7..18]).unwrap(), "THISISATEST");
     }
 }
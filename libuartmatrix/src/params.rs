@@ -0,0 +1,115 @@
+use core::convert::TryFrom;
+
+use crate::enums::FontType;
+use crate::error::UmxError;
+use crate::{MAX_FRAME_SIZE, PROTOCOL_VERSION};
+
+/// Capabilities reported by the device in reply to a parameter request.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DeviceParams {
+    pub protocol_version: u8,
+    pub width: u8,
+    pub height: u8,
+    pub text_rows: u8,
+    //Bit n is set when the font with id n is available
+    pub font_mask: u8,
+    pub firmware_version: (u8, u8, u8),
+    pub max_frame_size: u16,
+}
+
+impl DeviceParams {
+    pub const ENCODED_LENGTH: usize = 10;
+
+    pub fn supports_font(&self, font: FontType) -> bool {
+        let id: u8 = font.into();
+        self.font_mask & (1 << id) != 0
+    }
+
+    pub fn fonts(&self) -> impl Iterator<Item = FontType> + '_ {
+        FontType::ALL
+            .iter()
+            .copied()
+            .filter(move |font| self.supports_font(*font))
+    }
+
+    pub fn encode(&self) -> [u8; DeviceParams::ENCODED_LENGTH] {
+        let (major, minor, patch) = self.firmware_version;
+        [
+            self.protocol_version,
+            self.width,
+            self.height,
+            self.text_rows,
+            self.font_mask,
+            major,
+            minor,
+            patch,
+            (self.max_frame_size >> 8) as u8,
+            self.max_frame_size as u8,
+        ]
+    }
+
+    pub fn decode(data: &[u8]) -> Result<DeviceParams, UmxError> {
+        let data = <[u8; DeviceParams::ENCODED_LENGTH]>::try_from(data)
+            .map_err(|_| UmxError::MalformedPayload)?;
+        let [protocol_version, width, height, text_rows, font_mask, major, minor, patch, size_hi, size_lo] =
+            data;
+
+        Ok(DeviceParams {
+            protocol_version,
+            width,
+            height,
+            text_rows,
+            font_mask,
+            firmware_version: (major, minor, patch),
+            max_frame_size: ((size_hi as u16) << 8) | size_lo as u16,
+        })
+    }
+}
+
+impl Default for DeviceParams {
+    //The 64x32 three-row panel the protocol was designed around
+    fn default() -> Self {
+        DeviceParams {
+            protocol_version: PROTOCOL_VERSION,
+            width: 64,
+            height: 32,
+            text_rows: 3,
+            font_mask: 0b111,
+            firmware_version: (0, 0, 0),
+            max_frame_size: MAX_FRAME_SIZE as u16,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode_round_trip() {
+        let params = DeviceParams {
+            protocol_version: 2,
+            width: 128,
+            height: 64,
+            text_rows: 6,
+            font_mask: 0b101,
+            firmware_version: (1, 4, 2),
+            max_frame_size: 1024,
+        };
+
+        assert_eq!(DeviceParams::decode(&params.encode()), Ok(params));
+        assert_eq!(
+            params.fonts().collect::<Vec<_>>(),
+            vec![FontType::Default, FontType::Ibm]
+        );
+    }
+
+    #[test]
+    fn decode_rejects_wrong_length() {
+        let encoded = DeviceParams::default().encode();
+        assert_eq!(
+            DeviceParams::decode(&encoded[1..]),
+            Err(UmxError::MalformedPayload)
+        );
+    }
+}
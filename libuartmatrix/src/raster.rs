@@ -0,0 +1,333 @@
+//! Reference rasterizer for the draw commands.
+//!
+//! Defines which pixels each command lights up, so firmware, the emulator and host-side
+//! previews agree. The shape functions plot through a callback in signed coordinates
+//! and may reach outside the panel; [`draw_command`] clips them to a [`RasterTarget`].
+
+use crate::{Command, Point, RgbColor};
+
+/// A pixel buffer the rasterizer can draw into.
+pub trait RasterTarget {
+    fn width(&self) -> u32;
+
+    fn height(&self) -> u32;
+
+    /// Only called with coordinates inside the buffer.
+    fn set_pixel(&mut self, x: u32, y: u32, color: RgbColor);
+}
+
+/// Plots `command` into `target` and returns whether it is a drawing command at all.
+pub fn draw_command<T: RasterTarget + ?Sized>(target: &mut T, command: &Command) -> bool {
+    match command {
+        Command::DrawPixel { position, color } => {
+            clip(target, *color)(position.x as i32, position.y as i32)
+        }
+        Command::DrawRow { row, pixels } => {
+            for (x, color) in pixels.iter().enumerate() {
+                clip(target, *color)(x as i32, *row as i32);
+            }
+        }
+        Command::DrawLine {
+            point_1,
+            point_2,
+            thickness,
+            color,
+        } => line(*point_1, *point_2, *thickness, clip(target, *color)),
+        Command::DrawRectangle {
+            point_1,
+            point_2,
+            thickness,
+            color,
+            filled,
+        } => rectangle(
+            *point_1,
+            *point_2,
+            *thickness,
+            *filled,
+            clip(target, *color),
+        ),
+        Command::DrawTriangle {
+            point_1,
+            point_2,
+            point_3,
+            thickness,
+            color,
+            filled,
+        } => triangle(
+            [*point_1, *point_2, *point_3],
+            *thickness,
+            *filled,
+            clip(target, *color),
+        ),
+        Command::DrawCircle {
+            center,
+            radius,
+            thickness,
+            color,
+            filled,
+        } => circle(*center, *radius, *thickness, *filled, clip(target, *color)),
+        _ => return false,
+    }
+
+    true
+}
+
+fn clip<T: RasterTarget + ?Sized>(target: &mut T, color: RgbColor) -> impl FnMut(i32, i32) + '_ {
+    move |x, y| {
+        if x >= 0 && y >= 0 && (x as u32) < target.width() && (y as u32) < target.height() {
+            target.set_pixel(x as u32, y as u32, color);
+        }
+    }
+}
+
+fn brush<F: FnMut(i32, i32)>(x: i32, y: i32, thickness: u8, plot: &mut F) {
+    let size = thickness.max(1) as i32;
+    let offset = (size - 1) / 2;
+
+    for dy in 0..size {
+        for dx in 0..size {
+            plot(x - offset + dx, y - offset + dy);
+        }
+    }
+}
+
+/// Bresenham line, every point stamped with a square brush of the given thickness.
+pub fn line<F: FnMut(i32, i32)>(point_1: Point, point_2: Point, thickness: u8, mut plot: F) {
+    let (mut x, mut y) = (point_1.x as i32, point_1.y as i32);
+    let (x_end, y_end) = (point_2.x as i32, point_2.y as i32);
+    let dx = (x_end - x).abs();
+    let dy = -(y_end - y).abs();
+    let step_x = if x < x_end { 1 } else { -1 };
+    let step_y = if y < y_end { 1 } else { -1 };
+    let mut error = dx + dy;
+
+    loop {
+        brush(x, y, thickness, &mut plot);
+        if x == x_end && y == y_end {
+            break;
+        }
+
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += step_y;
+        }
+    }
+}
+
+/// Axis-aligned rectangle between two opposite corners, the border grows inwards.
+pub fn rectangle<F: FnMut(i32, i32)>(
+    point_1: Point,
+    point_2: Point,
+    thickness: u8,
+    filled: bool,
+    mut plot: F,
+) {
+    let (left, right) = min_max(point_1.x, point_2.x);
+    let (top, bottom) = min_max(point_1.y, point_2.y);
+    let thickness = thickness.max(1) as i32;
+
+    for y in top..=bottom {
+        for x in left..=right {
+            let border = x - left < thickness
+                || right - x < thickness
+                || y - top < thickness
+                || bottom - y < thickness;
+            if filled || border {
+                plot(x, y);
+            }
+        }
+    }
+}
+
+pub fn triangle<F: FnMut(i32, i32)>(points: [Point; 3], thickness: u8, filled: bool, mut plot: F) {
+    if filled {
+        fill_triangle(points, &mut plot);
+    }
+
+    for i in 0..3 {
+        line(points[i], points[(i + 1) % 3], thickness, &mut plot);
+    }
+}
+
+fn fill_triangle<F: FnMut(i32, i32)>(points: [Point; 3], plot: &mut F) {
+    let [a, b, c] = points;
+    let (left, _) = min_max(a.x.min(b.x), c.x);
+    let (_, right) = min_max(a.x.max(b.x), c.x);
+    let (top, _) = min_max(a.y.min(b.y), c.y);
+    let (_, bottom) = min_max(a.y.max(b.y), c.y);
+
+    //Signed area of (p, q, r), the sign tells which side of pq the point r is on
+    let edge = |p: Point, q: Point, x: i32, y: i32| {
+        (q.x as i32 - p.x as i32) * (y - p.y as i32) - (q.y as i32 - p.y as i32) * (x - p.x as i32)
+    };
+
+    for y in top..=bottom {
+        for x in left..=right {
+            let w_1 = edge(a, b, x, y);
+            let w_2 = edge(b, c, x, y);
+            let w_3 = edge(c, a, x, y);
+            let inside = (w_1 >= 0 && w_2 >= 0 && w_3 >= 0) || (w_1 <= 0 && w_2 <= 0 && w_3 <= 0);
+            if inside {
+                plot(x, y);
+            }
+        }
+    }
+}
+
+/// Circle around `center`, the ring of a hollow circle grows inwards from `radius`.
+pub fn circle<F: FnMut(i32, i32)>(
+    center: Point,
+    radius: u8,
+    thickness: u8,
+    filled: bool,
+    mut plot: F,
+) {
+    let radius = radius as i32;
+    //Adding the radius rounds the boundary to the nearest pixel instead of truncating it
+    let outer = radius * radius + radius;
+    let inner_radius = radius - thickness.max(1) as i32;
+    let inner = if filled || inner_radius < 0 {
+        -1
+    } else {
+        inner_radius * inner_radius + inner_radius
+    };
+
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            let distance = dx * dx + dy * dy;
+            if distance <= outer && distance > inner {
+                plot(center.x as i32 + dx, center.y as i32 + dy);
+            }
+        }
+    }
+}
+
+fn min_max(a: u8, b: u8) -> (i32, i32) {
+    (a.min(b) as i32, a.max(b) as i32)
+}
+
+/// Heap-allocated RGB buffer, black when created.
+#[cfg(any(test, feature = "std"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RgbBuffer {
+    width: u32,
+    height: u32,
+    pixels: Vec<RgbColor>,
+}
+
+#[cfg(any(test, feature = "std"))]
+impl RgbBuffer {
+    pub fn new(width: u32, height: u32) -> Self {
+        RgbBuffer {
+            width,
+            height,
+            pixels: vec![RgbColor::from_rgb(0, 0, 0); (width * height) as usize],
+        }
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> RgbColor {
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    /// Pixels in row-major order.
+    pub fn pixels(&self) -> &[RgbColor] {
+        &self.pixels
+    }
+
+    pub fn clear(&mut self) {
+        for pixel in self.pixels.iter_mut() {
+            *pixel = RgbColor::from_rgb(0, 0, 0);
+        }
+    }
+}
+
+#[cfg(any(test, feature = "std"))]
+impl RasterTarget for RgbBuffer {
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn set_pixel(&mut self, x: u32, y: u32, color: RgbColor) {
+        self.pixels[(y * self.width + x) as usize] = color;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points<F: FnOnce(&mut dyn FnMut(i32, i32))>(draw: F) -> Vec<(i32, i32)> {
+        let mut points = vec![];
+        draw(&mut |x, y| points.push((x, y)));
+        points.sort_unstable();
+        points.dedup();
+        points
+    }
+
+    #[test]
+    fn line_covers_both_ends() {
+        let plotted = points(|plot| line(Point { x: 0, y: 0 }, Point { x: 4, y: 2 }, 1, plot));
+
+        assert_eq!(plotted.len(), 5);
+        assert!(plotted.contains(&(0, 0)));
+        assert!(plotted.contains(&(4, 2)));
+    }
+
+    #[test]
+    fn hollow_rectangle_is_a_border() {
+        let plotted =
+            points(|plot| rectangle(Point { x: 3, y: 3 }, Point { x: 0, y: 0 }, 1, false, plot));
+
+        assert_eq!(plotted.len(), 12);
+        assert!(!plotted.contains(&(1, 1)));
+    }
+
+    #[test]
+    fn draw_command_clips_to_target() {
+        let mut buffer = RgbBuffer::new(8, 4);
+        let red = RgbColor::from_rgb(255, 0, 0);
+        let command = Command::DrawLine {
+            point_1: Point { x: 2, y: 2 },
+            point_2: Point { x: 200, y: 2 },
+            thickness: 1,
+            color: red,
+        };
+
+        assert!(draw_command(&mut buffer, &command));
+        assert_eq!(buffer.get(7, 2), red);
+        assert_eq!(buffer.get(1, 2), RgbColor::from_rgb(0, 0, 0));
+        assert!(!draw_command(&mut buffer, &Command::Clear));
+    }
+
+    #[test]
+    fn filled_shapes_cover_their_interior() {
+        let circle = points(|plot| circle(Point { x: 5, y: 5 }, 3, 1, true, plot));
+        assert!(circle.contains(&(5, 5)));
+        assert!(circle.contains(&(8, 5)));
+        assert!(!circle.contains(&(8, 8)));
+
+        let triangle = points(|plot| {
+            triangle(
+                [
+                    Point { x: 0, y: 0 },
+                    Point { x: 6, y: 0 },
+                    Point { x: 0, y: 6 },
+                ],
+                1,
+                true,
+                plot,
+            )
+        });
+        assert!(triangle.contains(&(2, 2)));
+        assert!(!triangle.contains(&(5, 5)));
+    }
+}
@@ -0,0 +1,174 @@
+use core::convert::TryFrom;
+
+use crate::enums::ResponseCode;
+use crate::error::UmxError;
+use crate::params::DeviceParams;
+use crate::writer::PayloadWriter;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NackReason {
+    BadCrc,
+    BadRow,
+    BadOpcode,
+    BufferFull,
+    BadPayload,
+    Other(u8),
+}
+
+impl From<NackReason> for u8 {
+    fn from(reason: NackReason) -> u8 {
+        match reason {
+            NackReason::BadCrc => 1,
+            NackReason::BadRow => 2,
+            NackReason::BadOpcode => 3,
+            NackReason::BufferFull => 4,
+            NackReason::BadPayload => 5,
+            NackReason::Other(code) => code,
+        }
+    }
+}
+
+impl From<u8> for NackReason {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => NackReason::BadCrc,
+            2 => NackReason::BadRow,
+            3 => NackReason::BadOpcode,
+            4 => NackReason::BufferFull,
+            5 => NackReason::BadPayload,
+            code => NackReason::Other(code),
+        }
+    }
+}
+
+/// A reply sent by the device, framed the same way as commands.
+///
+/// `opcode` is the raw opcode of the command being answered; it is kept as a byte so
+/// that a NACK for an opcode this library does not know can still be represented.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Response {
+    Ack { opcode: u8 },
+    Nack { opcode: u8, reason: NackReason },
+    Pong { version: u8 },
+    ParamReport(DeviceParams),
+}
+
+impl Response {
+    #[cfg(any(test, feature = "std"))]
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buffer = [0; crate::MAX_PAYLOAD_SIZE];
+        let length = self
+            .encode_into(&mut buffer)
+            .expect("every response fits in a single payload");
+
+        buffer[..length].to_vec()
+    }
+
+    /// Writes the payload into `buffer` and returns its length.
+    pub fn encode_into(&self, buffer: &mut [u8]) -> Result<usize, UmxError> {
+        let mut data = PayloadWriter::new(buffer);
+
+        match self {
+            Response::Ack { opcode } => data.extend(&[ResponseCode::Ack.into(), *opcode]),
+            Response::Nack { opcode, reason } => {
+                data.extend(&[ResponseCode::Nack.into(), *opcode, (*reason).into()])
+            }
+            Response::Pong { version } => data.extend(&[ResponseCode::Pong.into(), *version]),
+            Response::ParamReport(params) => {
+                data.push(ResponseCode::ParamReport.into());
+                data.extend(&params.encode());
+            }
+        }
+
+        data.finish()
+    }
+
+    pub fn decode(payload: &[u8]) -> Result<Response, UmxError> {
+        let (code, args) = payload.split_first().ok_or(UmxError::MalformedPayload)?;
+        let code = ResponseCode::try_from(*code).map_err(UmxError::UnknownResponse)?;
+
+        let response = match (code, args) {
+            (ResponseCode::Ack, [opcode]) => Response::Ack { opcode: *opcode },
+            (ResponseCode::Nack, [opcode, reason]) => Response::Nack {
+                opcode: *opcode,
+                reason: NackReason::from(*reason),
+            },
+            (ResponseCode::Pong, [version]) => Response::Pong { version: *version },
+            (ResponseCode::ParamReport, params) => {
+                Response::ParamReport(DeviceParams::decode(params)?)
+            }
+            _ => return Err(UmxError::MalformedPayload),
+        };
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{serialize_response, FrameDecoder};
+
+    #[test]
+    fn encode_decode_round_trip() {
+        let responses = [
+            Response::Ack { opcode: 2 },
+            Response::Nack {
+                opcode: 7,
+                reason: NackReason::BadRow,
+            },
+            Response::Nack {
+                opcode: 99,
+                reason: NackReason::Other(42),
+            },
+            Response::Pong { version: 2 },
+            Response::ParamReport(DeviceParams::default()),
+        ];
+
+        for response in responses.iter() {
+            assert_eq!(Response::decode(&response.encode()).as_ref(), Ok(response));
+        }
+    }
+
+    #[test]
+    fn decodes_framed_response() {
+        let frame = serialize_response(&Response::Nack {
+            opcode: 4,
+            reason: NackReason::BadCrc,
+        })
+        .unwrap();
+        let mut decoder = FrameDecoder::new();
+        let mut responses = vec![];
+        decoder.feed(&frame, |payload| responses.push(Response::decode(payload)));
+
+        assert_eq!(
+            responses,
+            vec![Ok(Response::Nack {
+                opcode: 4,
+                reason: NackReason::BadCrc
+            })]
+        );
+    }
+
+    #[test]
+    fn decode_rejects_malformed_payloads() {
+        assert_eq!(Response::decode(&[]), Err(UmxError::MalformedPayload));
+        assert_eq!(Response::decode(&[0x80]), Err(UmxError::MalformedPayload));
+        assert_eq!(
+            Response::decode(&[0x81, 1]),
+            Err(UmxError::MalformedPayload)
+        );
+        assert_eq!(
+            Response::decode(&[0x82, 1, 2]),
+            Err(UmxError::MalformedPayload)
+        );
+        assert_eq!(
+            Response::decode(&[0x83, 64, 32, 3]),
+            Err(UmxError::MalformedPayload)
+        );
+        assert_eq!(
+            Response::decode(&[0x42, 1]),
+            Err(UmxError::UnknownResponse(0x42))
+        );
+    }
+}
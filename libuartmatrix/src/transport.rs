@@ -0,0 +1,225 @@
+//! Links a [`MatrixClient`](crate::MatrixClient) can talk through.
+//!
+//! Besides serial ports (with the `serial` feature), panels can be reached through raw
+//! TCP connections to ser2net-style bridges and through Unix domain sockets.
+
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::time::Duration;
+
+/// A byte link to a panel, e.g. a serial port or a network bridge.
+pub trait Transport {
+    /// Writes all of `data`.
+    fn send(&mut self, data: &[u8]) -> io::Result<()>;
+
+    /// Reads what has arrived into `buffer`, waiting at most `timeout` for the first byte.
+    ///
+    /// Returns the number of bytes read, 0 if nothing arrived in time.
+    fn receive(&mut self, buffer: &mut [u8], timeout: Duration) -> io::Result<usize>;
+
+    /// Drops everything received but not read yet.
+    fn discard_input(&mut self) -> io::Result<()> {
+        let mut buffer = [0; 64];
+        while self.receive(&mut buffer, Duration::from_millis(0))? > 0 {}
+
+        Ok(())
+    }
+}
+
+impl<T: Transport + ?Sized> Transport for &mut T {
+    fn send(&mut self, data: &[u8]) -> io::Result<()> {
+        (**self).send(data)
+    }
+
+    fn receive(&mut self, buffer: &mut [u8], timeout: Duration) -> io::Result<usize> {
+        (**self).receive(buffer, timeout)
+    }
+
+    fn discard_input(&mut self) -> io::Result<()> {
+        (**self).discard_input()
+    }
+}
+
+impl<T: Transport + ?Sized> Transport for Box<T> {
+    fn send(&mut self, data: &[u8]) -> io::Result<()> {
+        (**self).send(data)
+    }
+
+    fn receive(&mut self, buffer: &mut [u8], timeout: Duration) -> io::Result<usize> {
+        (**self).receive(buffer, timeout)
+    }
+
+    fn discard_input(&mut self) -> io::Result<()> {
+        (**self).discard_input()
+    }
+}
+
+/// Stream sockets, which only differ in how their read timeout is set.
+trait Socket: Read + Write {
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()>;
+
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()>;
+}
+
+impl Socket for TcpStream {
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        TcpStream::set_read_timeout(self, timeout)
+    }
+
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        TcpStream::set_nonblocking(self, nonblocking)
+    }
+}
+
+#[cfg(unix)]
+impl Socket for UnixStream {
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        UnixStream::set_read_timeout(self, timeout)
+    }
+
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        UnixStream::set_nonblocking(self, nonblocking)
+    }
+}
+
+fn receive_from<S: Socket>(
+    socket: &mut S,
+    buffer: &mut [u8],
+    timeout: Duration,
+) -> io::Result<usize> {
+    //A zero read timeout is rejected, polling needs non-blocking mode instead
+    let result = if timeout == Duration::from_millis(0) {
+        socket.set_nonblocking(true)?;
+        let result = socket.read(buffer);
+        socket.set_nonblocking(false)?;
+        result
+    } else {
+        socket.set_read_timeout(Some(timeout))?;
+        socket.read(buffer)
+    };
+
+    match result {
+        Ok(0) if !buffer.is_empty() => Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "connection closed by the device",
+        )),
+        Ok(count) => Ok(count),
+        Err(e) if is_timeout(&e) => Ok(0),
+        Err(e) => Err(e),
+    }
+}
+
+fn is_timeout(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut | io::ErrorKind::Interrupted
+    )
+}
+
+impl Transport for TcpStream {
+    fn send(&mut self, data: &[u8]) -> io::Result<()> {
+        self.write_all(data)
+    }
+
+    fn receive(&mut self, buffer: &mut [u8], timeout: Duration) -> io::Result<usize> {
+        receive_from(self, buffer, timeout)
+    }
+}
+
+#[cfg(unix)]
+impl Transport for UnixStream {
+    fn send(&mut self, data: &[u8]) -> io::Result<()> {
+        self.write_all(data)
+    }
+
+    fn receive(&mut self, buffer: &mut [u8], timeout: Duration) -> io::Result<usize> {
+        receive_from(self, buffer, timeout)
+    }
+}
+
+#[cfg(feature = "serial")]
+impl Transport for dyn serialport::SerialPort {
+    fn send(&mut self, data: &[u8]) -> io::Result<()> {
+        self.write_all(data)
+    }
+
+    fn receive(&mut self, buffer: &mut [u8], timeout: Duration) -> io::Result<usize> {
+        //Some platforms treat a zero timeout as blocking forever
+        self.set_timeout(timeout.max(Duration::from_millis(1)))?;
+
+        match self.read(buffer) {
+            Ok(count) => Ok(count),
+            Err(e) if is_timeout(&e) => Ok(0),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn discard_input(&mut self) -> io::Result<()> {
+        Ok(self.clear(serialport::ClearBuffer::Input)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn tcp_stream_times_out_and_reports_closed_connections() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let device = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 3];
+            stream.read_exact(&mut request).unwrap();
+            stream.write_all(&request).unwrap();
+        });
+
+        let mut transport = TcpStream::connect(address).unwrap();
+        let mut buffer = [0; 8];
+        assert_eq!(
+            transport
+                .receive(&mut buffer, Duration::from_millis(10))
+                .unwrap(),
+            0
+        );
+        transport.discard_input().unwrap();
+
+        transport.send(b"UMX").unwrap();
+        device.join().unwrap();
+        let count = transport
+            .receive(&mut buffer, Duration::from_millis(500))
+            .unwrap();
+        assert_eq!(&buffer[..count], b"UMX");
+
+        let closed = transport.receive(&mut buffer, Duration::from_millis(500));
+        assert_eq!(closed.unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_stream_round_trip() {
+        let (mut transport, mut device) = UnixStream::pair().unwrap();
+
+        transport.send(&[1, 2, 3]).unwrap();
+        let mut request = [0; 3];
+        device.read_exact(&mut request).unwrap();
+        device.write_all(&request).unwrap();
+
+        let mut buffer = [0; 8];
+        let count = transport
+            .receive(&mut buffer, Duration::from_millis(500))
+            .unwrap();
+        assert_eq!(&buffer[..count], &[1, 2, 3]);
+        assert_eq!(
+            transport
+                .receive(&mut buffer, Duration::from_millis(0))
+                .unwrap(),
+            0
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use crate::error::UmxError;
+
+/// Cursor over a caller-provided payload buffer.
+///
+/// Writes past the end are dropped but still counted, so `finish` can report how
+/// much space the payload would have needed.
+pub(crate) struct PayloadWriter<'a> {
+    buffer: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> PayloadWriter<'a> {
+    pub(crate) fn new(buffer: &'a mut [u8]) -> Self {
+        PayloadWriter { buffer, len: 0 }
+    }
+
+    pub(crate) fn push(&mut self, byte: u8) {
+        self.extend(&[byte]);
+    }
+
+    pub(crate) fn extend(&mut self, bytes: &[u8]) {
+        let end = self.len + bytes.len();
+        if end <= self.buffer.len() {
+            self.buffer[self.len..end].clone_from_slice(bytes);
+        }
+        self.len = end;
+    }
+
+    pub(crate) fn finish(self) -> Result<usize, UmxError> {
+        if self.len > self.buffer.len() {
+            return Err(UmxError::BufferTooSmall {
+                needed: self.len,
+                available: self.buffer.len(),
+            });
+        }
+
+        Ok(self.len)
+    }
+}
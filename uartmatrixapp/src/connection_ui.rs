@@ -0,0 +1,146 @@
+use std::time::Duration;
+
+use iced::{Length, PickList, Row, Text, TextInput};
+
+use crate::{
+    helper_structs::{DataBits, FlowControl, Parity, StopBits},
+    port_settings::PortSettings,
+    Message,
+};
+
+/// The serial parameters being edited in the connection bar.
+pub struct ConnectionData {
+    pub baud_rate_state: iced::text_input::State,
+    pub baud_rate_value: String,
+
+    pub data_bits_state: iced::pick_list::State<DataBits>,
+    pub data_bits_value: DataBits,
+
+    pub parity_state: iced::pick_list::State<Parity>,
+    pub parity_value: Parity,
+
+    pub stop_bits_state: iced::pick_list::State<StopBits>,
+    pub stop_bits_value: StopBits,
+
+    pub flow_control_state: iced::pick_list::State<FlowControl>,
+    pub flow_control_value: FlowControl,
+
+    pub read_timeout_state: iced::text_input::State,
+    pub read_timeout_value: String,
+}
+
+impl ConnectionData {
+    pub fn new(settings: &PortSettings) -> Self {
+        ConnectionData {
+            baud_rate_state: iced::text_input::State::new(),
+            baud_rate_value: settings.baud_rate.to_string(),
+
+            data_bits_state: iced::pick_list::State::default(),
+            data_bits_value: settings.data_bits,
+
+            parity_state: iced::pick_list::State::default(),
+            parity_value: settings.parity,
+
+            stop_bits_state: iced::pick_list::State::default(),
+            stop_bits_value: settings.stop_bits,
+
+            flow_control_state: iced::pick_list::State::default(),
+            flow_control_value: settings.flow_control,
+
+            read_timeout_state: iced::text_input::State::new(),
+            read_timeout_value: settings.read_timeout.as_millis().to_string(),
+        }
+    }
+
+    /// Shows the settings of another port, keeping the widget states.
+    pub fn load(&mut self, settings: &PortSettings) {
+        self.baud_rate_value = settings.baud_rate.to_string();
+        self.data_bits_value = settings.data_bits;
+        self.parity_value = settings.parity;
+        self.stop_bits_value = settings.stop_bits;
+        self.flow_control_value = settings.flow_control;
+        self.read_timeout_value = settings.read_timeout.as_millis().to_string();
+    }
+
+    pub fn settings(&self) -> Result<PortSettings, String> {
+        let baud_rate = match self.baud_rate_value.trim().parse() {
+            Ok(baud_rate) if baud_rate > 0 => baud_rate,
+            _ => return Err(format!("Invalid baud rate '{}'", self.baud_rate_value)),
+        };
+        let read_timeout = match self.read_timeout_value.trim().parse() {
+            Ok(millis) if millis > 0 => Duration::from_millis(millis),
+            _ => {
+                return Err(format!(
+                    "Invalid read timeout '{}'",
+                    self.read_timeout_value
+                ))
+            }
+        };
+
+        Ok(PortSettings {
+            baud_rate,
+            data_bits: self.data_bits_value,
+            parity: self.parity_value,
+            stop_bits: self.stop_bits_value,
+            flow_control: self.flow_control_value,
+            read_timeout,
+        })
+    }
+}
+
+pub fn add_connection_ui(data: &mut ConnectionData) -> Row<'_, Message> {
+    let baud_rate = TextInput::new(
+        &mut data.baud_rate_state,
+        "Baud rate",
+        &data.baud_rate_value,
+        Message::BaudRateChanged,
+    )
+    .width(Length::Units(100));
+
+    let data_bits = PickList::new(
+        &mut data.data_bits_state,
+        &DataBits::ALL[..],
+        Some(data.data_bits_value),
+        Message::DataBitsSelected,
+    );
+
+    let parity = PickList::new(
+        &mut data.parity_state,
+        &Parity::ALL[..],
+        Some(data.parity_value),
+        Message::ParitySelected,
+    );
+
+    let stop_bits = PickList::new(
+        &mut data.stop_bits_state,
+        &StopBits::ALL[..],
+        Some(data.stop_bits_value),
+        Message::StopBitsSelected,
+    );
+
+    let flow_control = PickList::new(
+        &mut data.flow_control_state,
+        &FlowControl::ALL[..],
+        Some(data.flow_control_value),
+        Message::FlowControlSelected,
+    );
+
+    let read_timeout = TextInput::new(
+        &mut data.read_timeout_state,
+        "Read timeout (ms)",
+        &data.read_timeout_value,
+        Message::ReadTimeoutChanged,
+    )
+    .width(Length::Units(140));
+
+    Row::new()
+        .spacing(20)
+        .push(Text::new("Baud rate:"))
+        .push(baud_rate)
+        .push(data_bits)
+        .push(parity)
+        .push(stop_bits)
+        .push(flow_control)
+        .push(Text::new("Read timeout (ms):"))
+        .push(read_timeout)
+}
@@ -1,12 +1,24 @@
-use iced::{Button, Canvas, Checkbox, Column, Length, Row, Slider, Text, TextInput};
+use iced::{Button, Canvas, Checkbox, Column, Length, Radio, Row, Slider, Text, TextInput};
 
-use libuartmatrix::RgbColor;
+use libuartmatrix::{Command, Point, RgbColor};
 
+use crate::helper_structs::Tool;
+use crate::matrix_view::MatrixView;
 use crate::rect::Rect;
 use crate::text_mode_ui::COLOR_SLIDER_RANGE;
 use crate::Message;
 
+/// A mouse drag on the preview, in LED coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Drag {
+    pub start: Point,
+    pub current: Point,
+}
+
 pub struct DirectModeData {
+    pub tool: Tool,
+    pub drag: Option<Drag>,
+
     pub draw_pixel_btn: iced::button::State,
     pub draw_line_btn: iced::button::State,
     pub draw_rectangle_btn: iced::button::State,
@@ -75,6 +87,9 @@ pub struct DirectModeData {
 impl DirectModeData {
     pub fn new() -> Self {
         DirectModeData {
+            tool: Tool::default(),
+            drag: None,
+
             draw_pixel_btn: iced::button::State::new(),
             draw_line_btn: iced::button::State::new(),
             draw_rectangle_btn: iced::button::State::new(),
@@ -146,11 +161,74 @@ impl DirectModeData {
             color_slider_values: RgbColor::new(),
         }
     }
+
+    /// Thickness for shapes drawn with the mouse, 1 while the field holds no number.
+    pub fn thickness(&self) -> u8 {
+        self.shape_thickness_value.parse().unwrap_or(1)
+    }
+
+    /// Command for the selected tool dragged from `start` to `end`.
+    ///
+    /// The pencil draws while the mouse moves, so it has no shape to finish.
+    pub fn shape_command(&self, start: Point, end: Point) -> Option<Command> {
+        let color = self.color_slider_values;
+        let thickness = self.thickness();
+        let filled = self.filled_value;
+
+        let command = match self.tool {
+            Tool::Pencil => return None,
+            Tool::Line => Command::DrawLine {
+                point_1: start,
+                point_2: end,
+                thickness,
+                color,
+            },
+            Tool::Rectangle => Command::DrawRectangle {
+                point_1: start,
+                point_2: end,
+                thickness,
+                color,
+                filled,
+            },
+            //The apex sits where the drag started, the base on the opposite side of the box
+            Tool::Triangle => Command::DrawTriangle {
+                point_1: Point {
+                    x: ((start.x as u16 + end.x as u16) / 2) as u8,
+                    y: start.y,
+                },
+                point_2: Point {
+                    x: start.x,
+                    y: end.y,
+                },
+                point_3: end,
+                thickness,
+                color,
+                filled,
+            },
+            Tool::Circle => {
+                let dx = end.x as f32 - start.x as f32;
+                let dy = end.y as f32 - start.y as f32;
+
+                Command::DrawCircle {
+                    center: start,
+                    radius: (dx * dx + dy * dy).sqrt().round().min(255.0) as u8,
+                    thickness,
+                    color,
+                    filled,
+                }
+            }
+        };
+
+        Some(command)
+    }
 }
 
-pub fn add_direct_mode_ui(data: &mut DirectModeData) -> (Column<Message>, Column<Message>) {
+pub fn add_direct_mode_ui(
+    data: &mut DirectModeData,
+    preview: Canvas<Message, MatrixView>,
+) -> (Column<Message>, Column<Message>) {
     let mut left_column = Column::new().max_width(800).spacing(20);
-    let mut right_column = Column::new().max_width(600).spacing(20);
+    let right_column = Column::new().max_width(600).spacing(20).push(preview);
 
     let load_file_button = Button::new(&mut data.load_file_btn, Text::new("Load image..."))
         .on_press(Message::LoadImage);
@@ -378,6 +456,17 @@ pub fn add_direct_mode_ui(data: &mut DirectModeData) -> (Column<Message>, Column
         .push(circle_radius_text_input)
         .push(draw_circle_button);
 
+    let mut tool_row = Row::new().spacing(20).push(Text::new("Tool:"));
+    for tool in Tool::ALL.iter() {
+        let radio = Radio::new(
+            *tool,
+            tool.to_string(),
+            Some(data.tool),
+            Message::ToolSelected,
+        );
+        tool_row = tool_row.push(radio);
+    }
+
     left_column = left_column
         .push(load_file_button)
         .push(clear_screen_button)
@@ -391,6 +480,10 @@ pub fn add_direct_mode_ui(data: &mut DirectModeData) -> (Column<Message>, Column
 
     left_column = left_column
         .push(common_row)
+        .push(Text::new(
+            "Drag on the preview to draw, or enter coordinates below",
+        ))
+        .push(tool_row)
         .push(pixel_row)
         .push(line_row)
         .push(rectangle_row)
@@ -1,3 +1,5 @@
+use libuartmatrix::enums::FontType;
+
 #[derive(Debug, Copy, Clone)]
 pub struct RgbSlidersState {
     pub r: iced::slider::State,
@@ -82,8 +84,24 @@ pub enum Font {
     Ibm,
 }
 
-impl Font {
-    pub const ALL: [Font; 3] = [Font::Default, Font::Pro, Font::Ibm];
+impl From<FontType> for Font {
+    fn from(font: FontType) -> Font {
+        match font {
+            FontType::Default => Font::Default,
+            FontType::Pro => Font::Pro,
+            FontType::Ibm => Font::Ibm,
+        }
+    }
+}
+
+impl From<Font> for FontType {
+    fn from(font: Font) -> FontType {
+        match font {
+            Font::Default => FontType::Default,
+            Font::Pro => FontType::Pro,
+            Font::Ibm => FontType::Ibm,
+        }
+    }
 }
 
 impl Default for Font {
@@ -105,3 +123,188 @@ impl std::fmt::Display for Font {
         )
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tool {
+    #[default]
+    Pencil,
+    Line,
+    Rectangle,
+    Triangle,
+    Circle,
+}
+
+impl Tool {
+    pub const ALL: [Tool; 5] = [
+        Tool::Pencil,
+        Tool::Line,
+        Tool::Rectangle,
+        Tool::Triangle,
+        Tool::Circle,
+    ];
+}
+
+impl std::fmt::Display for Tool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Tool::Pencil => "Pencil",
+                Tool::Line => "Line",
+                Tool::Rectangle => "Rectangle",
+                Tool::Triangle => "Triangle",
+                Tool::Circle => "Circle",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataBits {
+    Five,
+    Six,
+    Seven,
+    Eight,
+}
+
+impl DataBits {
+    pub const ALL: [DataBits; 4] = [
+        DataBits::Five,
+        DataBits::Six,
+        DataBits::Seven,
+        DataBits::Eight,
+    ];
+}
+
+impl From<DataBits> for serialport::DataBits {
+    fn from(data_bits: DataBits) -> serialport::DataBits {
+        match data_bits {
+            DataBits::Five => serialport::DataBits::Five,
+            DataBits::Six => serialport::DataBits::Six,
+            DataBits::Seven => serialport::DataBits::Seven,
+            DataBits::Eight => serialport::DataBits::Eight,
+        }
+    }
+}
+
+impl std::fmt::Display for DataBits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                DataBits::Five => "5 data bits",
+                DataBits::Six => "6 data bits",
+                DataBits::Seven => "7 data bits",
+                DataBits::Eight => "8 data bits",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parity {
+    None,
+    Odd,
+    Even,
+}
+
+impl Parity {
+    pub const ALL: [Parity; 3] = [Parity::None, Parity::Odd, Parity::Even];
+}
+
+impl From<Parity> for serialport::Parity {
+    fn from(parity: Parity) -> serialport::Parity {
+        match parity {
+            Parity::None => serialport::Parity::None,
+            Parity::Odd => serialport::Parity::Odd,
+            Parity::Even => serialport::Parity::Even,
+        }
+    }
+}
+
+impl std::fmt::Display for Parity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Parity::None => "No parity",
+                Parity::Odd => "Odd parity",
+                Parity::Even => "Even parity",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopBits {
+    One,
+    Two,
+}
+
+impl StopBits {
+    pub const ALL: [StopBits; 2] = [StopBits::One, StopBits::Two];
+}
+
+impl From<StopBits> for serialport::StopBits {
+    fn from(stop_bits: StopBits) -> serialport::StopBits {
+        match stop_bits {
+            StopBits::One => serialport::StopBits::One,
+            StopBits::Two => serialport::StopBits::Two,
+        }
+    }
+}
+
+impl std::fmt::Display for StopBits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                StopBits::One => "1 stop bit",
+                StopBits::Two => "2 stop bits",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowControl {
+    None,
+    Software,
+    Hardware,
+}
+
+impl FlowControl {
+    pub const ALL: [FlowControl; 3] = [
+        FlowControl::None,
+        FlowControl::Software,
+        FlowControl::Hardware,
+    ];
+}
+
+impl From<FlowControl> for serialport::FlowControl {
+    fn from(flow_control: FlowControl) -> serialport::FlowControl {
+        match flow_control {
+            FlowControl::None => serialport::FlowControl::None,
+            FlowControl::Software => serialport::FlowControl::Software,
+            FlowControl::Hardware => serialport::FlowControl::Hardware,
+        }
+    }
+}
+
+impl std::fmt::Display for FlowControl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                FlowControl::None => "No flow control",
+                FlowControl::Software => "XON/XOFF",
+                FlowControl::Hardware => "RTS/CTS",
+            }
+        )
+    }
+}
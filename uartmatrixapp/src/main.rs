@@ -1,27 +1,50 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
 use iced::{
-    Application, Button, Clipboard, Column, Command, Container, Element, PickList, Row, Settings,
-    Text,
+    Application, Button, Canvas, Clipboard, Column, Command, Container, Element, Length, PickList,
+    Row, Settings, Subscription, Text,
 };
 use image::imageops::FilterType;
 use image::io::Reader;
 use native_dialog::FileDialog;
-use serialport::SerialPort;
 
+use connection_ui::{add_connection_ui, ConnectionData};
 use direct_mode_ui::{add_direct_mode_ui, DirectModeData};
 use libuartmatrix::enums::DisplayMode;
+use libuartmatrix::{DeviceParams, Pacing, Point};
+use uartmatrixdevice::ProtocolEngine;
+use uartmatrixemu::{Emulator, MatrixState};
 
 use crate::{
-    helper_structs::{Animation, Direction, Font},
+    direct_mode_ui::Drag,
+    helper_structs::{Animation, DataBits, Direction, FlowControl, Font, Parity, StopBits, Tool},
+    matrix_view::MatrixView,
+    port_settings::PortSettings,
+    scan::Port,
     serial::*,
     text_mode_ui::{add_text_mode_ui, TextModeData},
+    worker::{Job, Worker, WorkerEvent},
     AppState::TextMode,
 };
 
+mod connection_ui;
 mod direct_mode_ui;
 mod helper_structs;
+mod matrix_view;
+mod port_settings;
 mod rect;
+mod scan;
 mod serial;
 mod text_mode_ui;
+mod worker;
+
+//Size of one LED in the preview, direct mode zooms in further for drawing
+const PREVIEW_SCALE: u16 = 6;
+const DRAWING_SCALE: u16 = 9;
+const PREVIEW_FRAME_INTERVAL: Duration = Duration::from_millis(33);
 
 pub fn main() -> iced::Result {
     App::run(Settings::default())
@@ -36,9 +59,17 @@ enum AppState {
 #[derive(Debug, Clone)]
 pub enum Message {
     ChangeMode,
-    PortSelected(String),
+    PortSelected(Port),
     RefreshDevices,
+    DetectDevices,
+    BaudRateChanged(String),
+    DataBitsSelected(DataBits),
+    ParitySelected(Parity),
+    StopBitsSelected(StopBits),
+    FlowControlSelected(FlowControl),
+    ReadTimeoutChanged(String),
     ConnectDevice,
+    WorkOffline,
     LoadImage,
     Ping,
     TextChanged(String, usize),
@@ -66,20 +97,38 @@ pub enum Message {
     TriangleCoordChanged(String, i32, i32),
     CircleCoordChanged(String, i32),
     ClearScreen,
+    ToolSelected(Tool),
+    CanvasPressed(Point),
+    CanvasDragged(Point),
+    CanvasReleased(Point),
+    Worker(WorkerEvent),
+    Tick,
 }
 
 struct App {
     state: AppState,
 
     connect_btn: iced::button::State,
+    offline_btn: iced::button::State,
     refresh_btn: iced::button::State,
+    detect_btn: iced::button::State,
     ping_btn: iced::button::State,
     change_mode_btn: iced::button::State,
 
-    device: Option<Box<dyn SerialPort>>,
-    port_list: Vec<String>,
-    port_select_state: iced::pick_list::State<String>,
-    port_select_value: Option<String>,
+    //Owns the serial port, so that writing to it never blocks the UI
+    worker: Worker,
+    busy: bool,
+    status: String,
+    device_params: DeviceParams,
+    //Plays every packet sent, so that the preview shows what the device displays
+    preview: Emulator,
+    started: Instant,
+    port_list: Vec<Port>,
+    port_select_state: iced::pick_list::State<Port>,
+    port_select_value: Option<Port>,
+    connection_data: ConnectionData,
+    //The serial parameters last used for each port, also kept across runs
+    port_settings: HashMap<String, PortSettings>,
 
     text_mode_data: TextModeData,
     direct_mode_data: DirectModeData,
@@ -95,17 +144,26 @@ impl Application for App {
             App {
                 state: AppState::NotConnected,
                 connect_btn: iced::button::State::new(),
+                offline_btn: iced::button::State::new(),
                 refresh_btn: iced::button::State::new(),
+                detect_btn: iced::button::State::new(),
                 ping_btn: iced::button::State::new(),
                 change_mode_btn: iced::button::State::new(),
 
-                device: None,
+                worker: Worker::spawn(Pacing::default()),
+                busy: false,
+                status: String::from("Not connected"),
+                device_params: DeviceParams::default(),
+                preview: Emulator::default(),
+                started: Instant::now(),
 
                 port_list: vec![],
                 port_select_state: iced::pick_list::State::default(),
                 port_select_value: None,
+                connection_data: ConnectionData::new(&PortSettings::default()),
+                port_settings: port_settings::load(),
 
-                text_mode_data: TextModeData::new(),
+                text_mode_data: TextModeData::new(&DeviceParams::default()),
                 direct_mode_data: DirectModeData::new(),
             },
             Command::none(),
@@ -118,27 +176,60 @@ impl Application for App {
 
     fn update(&mut self, message: Message, _clipboard: &mut Clipboard) -> Command<Message> {
         match message {
-            Message::PortSelected(port) => {
-                self.port_select_value = Some(port);
-            }
+            Message::PortSelected(port) => self.select_port(port),
             Message::RefreshDevices => {
                 enumerate_ports(&mut self.port_list);
             }
+            Message::DetectDevices => {
+                enumerate_ports(&mut self.port_list);
+                if self.port_list.is_empty() {
+                    self.status = String::from("No serial ports found");
+                    return Command::none();
+                }
+
+                let ports = self
+                    .port_list
+                    .iter()
+                    .map(|port| (port.name.clone(), self.settings_for(&port.name)))
+                    .collect();
+                self.status = format!("Looking for matrices on {} ports...", self.port_list.len());
+                self.worker.send(Job::Scan(ports));
+            }
+            Message::BaudRateChanged(value) => self.connection_data.baud_rate_value = value,
+            Message::DataBitsSelected(value) => self.connection_data.data_bits_value = value,
+            Message::ParitySelected(value) => self.connection_data.parity_value = value,
+            Message::StopBitsSelected(value) => self.connection_data.stop_bits_value = value,
+            Message::FlowControlSelected(value) => self.connection_data.flow_control_value = value,
+            Message::ReadTimeoutChanged(value) => self.connection_data.read_timeout_value = value,
             Message::ConnectDevice => {
-                if let Some(port) = self.port_select_value.clone() {
-                    let sp = serialport::new(port, 115200).open();
-                    match sp {
-                        Ok(s_port) => {
-                            self.state = AppState::TextMode;
-                            self.device = Some(s_port);
-                        }
+                if let Some(port) = self.port_select_value.clone().map(|port| port.name) {
+                    let settings = match self.connection_data.settings() {
+                        Ok(settings) => settings,
                         Err(e) => {
-                            println!("{}", e)
+                            self.status = e;
+                            return Command::none();
                         }
-                    }
+                    };
+
+                    self.port_settings.insert(port.clone(), settings);
+                    //Failing to remember the settings must not keep anyone from connecting
+                    let _ = port_settings::save(&self.port_settings);
+
+                    self.status = format!("Connecting to {}...", port);
+                    self.worker.send(Job::Connect { port, settings });
                 }
             }
 
+            Message::WorkOffline => {
+                //Without a device everything is only played on the preview
+                self.worker.send(Job::Disconnect);
+                self.status = String::from("Working offline");
+                self.device_params = DeviceParams::default();
+                self.text_mode_data = TextModeData::new(&self.device_params);
+                self.preview = Emulator::with_params(self.device_params);
+                self.state = AppState::TextMode;
+            }
+
             Message::TextChanged(content, id) => {
                 let text_row = &mut self.text_mode_data.text_rows_values[id];
                 *text_row = content;
@@ -175,18 +266,25 @@ impl Application for App {
             }
 
             Message::SendText => {
-                if let TextMode = &mut self.state {
-                    send_text(
-                        self.device.as_mut().unwrap().as_mut(),
+                //The button is disabled as well, this keeps unrenderable text off the wire
+                if !self.text_mode_data.text_is_sendable() {
+                    self.status =
+                        String::from("Text contains characters the selected fonts cannot show");
+                } else if let TextMode = &mut self.state {
+                    let result = send_text(
+                        &mut Link::new(&self.worker, &mut self.preview),
                         &self.text_mode_data.text_rows_values,
                     );
+                    if let Err(e) = result {
+                        self.status = e;
+                    }
                 }
             }
 
             Message::SendColors => {
                 if let TextMode = &mut self.state {
                     send_colors(
-                        self.device.as_mut().unwrap().as_mut(),
+                        &mut Link::new(&self.worker, &mut self.preview),
                         &self.text_mode_data.color_slider_values,
                     );
                 }
@@ -195,7 +293,7 @@ impl Application for App {
             Message::SendAnims => {
                 if let TextMode = &mut self.state {
                     send_animations(
-                        self.device.as_mut().unwrap().as_mut(),
+                        &mut Link::new(&self.worker, &mut self.preview),
                         &self.text_mode_data.anim_select_values,
                         &self.text_mode_data.anim_speed_values,
                         &self.text_mode_data.anim_direction_values,
@@ -206,7 +304,7 @@ impl Application for App {
             Message::SendFonts => {
                 if let TextMode = &mut self.state {
                     send_fonts(
-                        self.device.as_mut().unwrap().as_mut(),
+                        &mut Link::new(&self.worker, &mut self.preview),
                         &self.text_mode_data.font_values,
                     );
                 }
@@ -218,14 +316,17 @@ impl Application for App {
                         //send command to switch mode
                         self.state = AppState::DirectMode;
                         send_change_mode(
-                            self.device.as_mut().unwrap().as_mut(),
+                            &mut Link::new(&self.worker, &mut self.preview),
                             DisplayMode::Direct,
                         );
                     }
                     AppState::DirectMode => {
                         //send command to switch mode
                         self.state = AppState::TextMode;
-                        send_change_mode(self.device.as_mut().unwrap().as_mut(), DisplayMode::Text);
+                        send_change_mode(
+                            &mut Link::new(&self.worker, &mut self.preview),
+                            DisplayMode::Text,
+                        );
                     }
                     _ => {}
                 }
@@ -244,12 +345,16 @@ impl Application for App {
                         Ok(img) => {
                             let img = img.decode();
                             if let Ok(img) = img {
-                                let img = img.resize(64, 32, FilterType::Lanczos3);
+                                let img = img.resize(
+                                    self.device_params.width as u32,
+                                    self.device_params.height as u32,
+                                    FilterType::Lanczos3,
+                                );
                                 let img = img.into_rgb8();
-                                send_image(self.device.as_mut().unwrap().as_mut(), img);
+                                send_image(&mut Link::new(&self.worker, &mut self.preview), img);
                             }
                         }
-                        Err(_e) => println!("Error opening file"),
+                        Err(e) => self.status = format!("Error opening file: {}", e),
                     }
                 };
             }
@@ -325,7 +430,7 @@ impl Application for App {
 
             Message::DrawPixel => {
                 send_draw_pixel(
-                    self.device.as_mut().unwrap().as_mut(),
+                    &mut Link::new(&self.worker, &mut self.preview),
                     &self.direct_mode_data.pixel_x_text_input,
                     &self.direct_mode_data.pixel_y_text_input,
                     &self.direct_mode_data.color_slider_values,
@@ -334,7 +439,7 @@ impl Application for App {
 
             Message::DrawLine => {
                 send_draw_line(
-                    self.device.as_mut().unwrap().as_mut(),
+                    &mut Link::new(&self.worker, &mut self.preview),
                     &self.direct_mode_data.line_x_1_text_input,
                     &self.direct_mode_data.line_y_1_text_input,
                     &self.direct_mode_data.line_x_2_text_input,
@@ -346,7 +451,7 @@ impl Application for App {
 
             Message::DrawRectangle => {
                 send_draw_rectangle(
-                    self.device.as_mut().unwrap().as_mut(),
+                    &mut Link::new(&self.worker, &mut self.preview),
                     &self.direct_mode_data.rectangle_x_1_text_input,
                     &self.direct_mode_data.rectangle_y_1_text_input,
                     &self.direct_mode_data.rectangle_x_2_text_input,
@@ -359,7 +464,7 @@ impl Application for App {
 
             Message::DrawTriangle => {
                 send_draw_triangle(
-                    self.device.as_mut().unwrap().as_mut(),
+                    &mut Link::new(&self.worker, &mut self.preview),
                     &self.direct_mode_data.triangle_x_1_text_input,
                     &self.direct_mode_data.triangle_y_1_text_input,
                     &self.direct_mode_data.triangle_x_2_text_input,
@@ -374,7 +479,7 @@ impl Application for App {
 
             Message::DrawCircle => {
                 send_draw_circle(
-                    self.device.as_mut().unwrap().as_mut(),
+                    &mut Link::new(&self.worker, &mut self.preview),
                     &self.direct_mode_data.circle_x_text_input,
                     &self.direct_mode_data.circle_y_text_input,
                     &self.direct_mode_data.circle_radius_text_input,
@@ -385,7 +490,64 @@ impl Application for App {
             }
 
             Message::ClearScreen => {
-                send_clear_screen(self.device.as_mut().unwrap().as_mut());
+                send_clear_screen(&mut Link::new(&self.worker, &mut self.preview));
+            }
+
+            Message::ToolSelected(tool) => {
+                self.direct_mode_data.tool = tool;
+                self.direct_mode_data.drag = None;
+            }
+
+            Message::CanvasPressed(position) => {
+                self.direct_mode_data.drag = Some(Drag {
+                    start: position,
+                    current: position,
+                });
+
+                if self.direct_mode_data.tool == Tool::Pencil {
+                    send_command(
+                        &mut Link::new(&self.worker, &mut self.preview),
+                        &libuartmatrix::Command::DrawPixel {
+                            position,
+                            color: self.direct_mode_data.color_slider_values,
+                        },
+                    );
+                }
+            }
+
+            Message::CanvasDragged(position) => {
+                if let Some(drag) = &mut self.direct_mode_data.drag {
+                    let previous = drag.current;
+                    drag.current = position;
+
+                    //Connecting the samples keeps fast strokes free of gaps
+                    if self.direct_mode_data.tool == Tool::Pencil && previous != position {
+                        send_command(
+                            &mut Link::new(&self.worker, &mut self.preview),
+                            &libuartmatrix::Command::DrawLine {
+                                point_1: previous,
+                                point_2: position,
+                                thickness: self.direct_mode_data.thickness(),
+                                color: self.direct_mode_data.color_slider_values,
+                            },
+                        );
+                    }
+                }
+            }
+
+            Message::CanvasReleased(position) => {
+                if let Some(drag) = self.direct_mode_data.drag.take() {
+                    if let Some(command) = self.direct_mode_data.shape_command(drag.start, position)
+                    {
+                        send_command(&mut Link::new(&self.worker, &mut self.preview), &command);
+                    }
+                }
+            }
+
+            Message::Worker(event) => self.handle_worker_event(event),
+
+            Message::Tick => {
+                //Nothing changes, the animated preview only needs to be redrawn
             }
 
             _ => {}
@@ -394,7 +556,21 @@ impl Application for App {
         Command::none()
     }
 
+    fn subscription(&self) -> Subscription<Message> {
+        let worker = self.worker.subscription().map(Message::Worker);
+
+        if self.preview_state().is_animated() {
+            let ticks = iced::time::every(PREVIEW_FRAME_INTERVAL).map(|_| Message::Tick);
+            Subscription::batch(vec![worker, ticks])
+        } else {
+            worker
+        }
+    }
+
     fn view(&mut self) -> Element<Message> {
+        //Needs all of `self`, so it has to happen before the widgets borrow their state
+        let preview_image = self.preview_state().render(self.started.elapsed());
+
         let port_list = PickList::new(
             &mut self.port_select_state,
             self.port_list.clone(),
@@ -404,13 +580,17 @@ impl Application for App {
 
         let refresh_button = Button::new(&mut self.refresh_btn, Text::new("Refresh"))
             .on_press(Message::RefreshDevices);
+        let mut detect_button = Button::new(&mut self.detect_btn, Text::new("Auto-detect"));
 
         let mut connect_button = Button::new(&mut self.connect_btn, Text::new("Connect"));
+        let mut offline_button = Button::new(&mut self.offline_btn, Text::new("Work offline"));
         // let mut ping_button = Button::new(&mut self.ping_btn, Text::new("Ping"));
         let mut change_mode_button =
             Button::new(&mut self.change_mode_btn, Text::new("Change display mode"));
         if let AppState::NotConnected = self.state {
             connect_button = connect_button.on_press(Message::ConnectDevice);
+            detect_button = detect_button.on_press(Message::DetectDevices);
+            offline_button = offline_button.on_press(Message::WorkOffline);
         } else {
             // ping_button = ping_button.on_press(Message::Ping);
             change_mode_button = change_mode_button.on_press(Message::ChangeMode);
@@ -420,26 +600,54 @@ impl Application for App {
             .spacing(20)
             .push(port_list)
             .push(refresh_button)
+            .push(detect_button)
             .push(connect_button)
+            .push(offline_button)
             // .push(ping_button)
             .push(change_mode_button);
 
         let mut content = Column::new().push(controls).spacing(20);
+        if let AppState::NotConnected = self.state {
+            content = content.push(add_connection_ui(&mut self.connection_data));
+        }
+
+        let drawing = matches!(self.state, AppState::DirectMode);
+        let scale = if drawing {
+            DRAWING_SCALE
+        } else {
+            PREVIEW_SCALE
+        };
+        let preview = Canvas::new(MatrixView {
+            image: preview_image,
+            drawing,
+            dragging: self.direct_mode_data.drag.is_some(),
+        })
+        .width(Length::Units(self.device_params.width as u16 * scale))
+        .height(Length::Units(self.device_params.height as u16 * scale));
 
         match &mut self.state {
             AppState::TextMode => {
-                let (left_column, right_column) = add_text_mode_ui(&mut self.text_mode_data);
+                let (left_column, right_column) =
+                    add_text_mode_ui(&mut self.text_mode_data, preview);
                 let text_ui = Row::new().push(left_column).push(right_column).spacing(20);
                 content = content.push(text_ui);
             }
             AppState::DirectMode => {
-                let (left_column, right_column) = add_direct_mode_ui(&mut self.direct_mode_data);
+                let (left_column, right_column) =
+                    add_direct_mode_ui(&mut self.direct_mode_data, preview);
                 let ui = Row::new().push(left_column).push(right_column).spacing(20);
                 content = content.push(ui);
             }
             _ => {}
         }
 
+        let status = if self.busy {
+            format!("{} (sending...)", self.status)
+        } else {
+            self.status.clone()
+        };
+        content = content.push(Text::new(status).size(16));
+
         Container::new(content)
             .center_x()
             .center_y()
@@ -448,14 +656,118 @@ impl Application for App {
     }
 }
 
-impl App {}
+impl App {
+    fn handle_worker_event(&mut self, event: WorkerEvent) {
+        match event {
+            WorkerEvent::Connected { port, params } => {
+                self.device_params = match params {
+                    Some(params) => {
+                        self.status = format!("Connected to {}", port);
+                        params
+                    }
+                    None => {
+                        self.status = format!(
+                            "Connected to {}, device did not report parameters, assuming defaults",
+                            port
+                        );
+                        DeviceParams::default()
+                    }
+                };
+                self.text_mode_data = TextModeData::new(&self.device_params);
+                self.preview = Emulator::with_params(self.device_params);
+                self.state = AppState::TextMode;
+            }
+            WorkerEvent::ConnectFailed(e) => self.status = e,
+            WorkerEvent::Failed { label, error } => self.status = format!("{}: {}", label, error),
+            WorkerEvent::TimedPacing => {
+                self.status =
+                    String::from("Device does not acknowledge frames, falling back to timed pacing")
+            }
+            WorkerEvent::Scanned(ports) => {
+                let found: Vec<Port> = ports
+                    .iter()
+                    .filter(|port| port.device.is_some())
+                    .cloned()
+                    .collect();
+
+                //Keep the selection, now with what was found on it
+                let selected = self.port_select_value.take().and_then(|selected| {
+                    ports
+                        .iter()
+                        .find(|port| port.name == selected.name)
+                        .cloned()
+                });
+                self.port_select_value = selected;
+                self.port_list = ports;
+
+                match found.as_slice() {
+                    [port] => {
+                        self.status = format!("Found a matrix on {}", port.name);
+                        self.select_port(port.clone());
+                    }
+                    [] => {
+                        self.status = String::from(
+                            "No matrix answered, check the cables and serial parameters",
+                        )
+                    }
+                    _ => self.status = format!("{} matrices answered, pick one", found.len()),
+                }
+            }
+            WorkerEvent::Busy => self.busy = true,
+            WorkerEvent::Idle => self.busy = false,
+        }
+    }
+
+    fn select_port(&mut self, port: Port) {
+        self.connection_data.load(&self.settings_for(&port.name));
+        self.port_select_value = Some(port);
+    }
+
+    /// The serial parameters last used for `port`.
+    fn settings_for(&self, port: &str) -> PortSettings {
+        self.port_settings.get(port).copied().unwrap_or_default()
+    }
 
-fn enumerate_ports(port_list: &mut Vec<String>) {
-    let ports = serialport::available_ports();
-    if let Ok(ports) = ports {
-        *port_list = ports
-            .iter()
-            .map(|port_info| port_info.port_name.clone())
-            .collect();
+    /// What the device shows, with the unsent text mode settings or the shape being
+    /// dragged applied on top.
+    fn preview_state(&self) -> MatrixState {
+        let mut state = self.preview.state().clone();
+        match self.state {
+            AppState::TextMode => self.text_mode_data.apply_to(&mut state),
+            AppState::DirectMode => {
+                let data = &self.direct_mode_data;
+                let shape = data
+                    .drag
+                    .and_then(|drag| data.shape_command(drag.start, drag.current));
+
+                if let Some(shape) = shape {
+                    let mut engine = ProtocolEngine::new(state);
+                    engine.handle_command(&shape);
+                    state = engine.into_backend();
+                }
+            }
+            AppState::NotConnected => {}
+        }
+        state
+    }
+}
+
+fn enumerate_ports(port_list: &mut Vec<Port>) {
+    //Without a fresh list the extra ports would be appended once more
+    *port_list = serialport::available_ports()
+        .map(|ports| {
+            ports
+                .iter()
+                .map(|port_info| Port::new(port_info.port_name.clone()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    //Pseudo-terminals such as the one served by umx-pty are not enumerated by the OS
+    if let Ok(extra_ports) = std::env::var("UMX_EXTRA_PORTS") {
+        port_list.extend(
+            std::env::split_paths(&extra_ports)
+                .map(|path| Port::new(path.to_string_lossy().into_owned())),
+        );
     }
 }
@@ -0,0 +1,134 @@
+use iced::{
+    mouse,
+    widget::canvas::{
+        event::{self, Event},
+        Cursor, Frame, Geometry, Program,
+    },
+    Color, Point, Rectangle, Size,
+};
+use image::RgbImage;
+
+use crate::Message;
+
+//Share of each LED cell left dark, so that single pixels stay distinguishable
+const LED_GAP: f32 = 0.15;
+
+/// Draws a matrix image scaled to the canvas, one square per LED.
+///
+/// With `drawing` set, pressing, dragging and releasing the left mouse button report
+/// the LED under the cursor through the `Canvas*` messages.
+#[derive(Debug)]
+pub struct MatrixView {
+    pub image: RgbImage,
+    pub drawing: bool,
+    pub dragging: bool,
+}
+
+impl MatrixView {
+    /// Size of one LED and the top left corner of the panel within `size`.
+    fn layout(&self, size: Size) -> (f32, Point) {
+        let (width, height) = self.image.dimensions();
+
+        //Keep the LEDs square and center the panel in the available space
+        let cell = (size.width / width as f32).min(size.height / height as f32);
+        let origin = Point::new(
+            (size.width - cell * width as f32) / 2.0,
+            (size.height - cell * height as f32) / 2.0,
+        );
+
+        (cell, origin)
+    }
+
+    /// LED under the cursor, outside positions are moved to the nearest edge if `clamp` is set.
+    fn led_at(
+        &self,
+        bounds: Rectangle,
+        cursor: Cursor,
+        clamp: bool,
+    ) -> Option<libuartmatrix::Point> {
+        let (width, height) = self.image.dimensions();
+        let (cell, origin) = self.layout(bounds.size());
+        let position = cursor.position_from(bounds.position())?;
+
+        let x = ((position.x - origin.x) / cell).floor();
+        let y = ((position.y - origin.y) / cell).floor();
+        let inside = x >= 0.0 && y >= 0.0 && x < width as f32 && y < height as f32;
+        if !inside && !clamp {
+            return None;
+        }
+
+        Some(libuartmatrix::Point {
+            x: x.max(0.0).min(width as f32 - 1.0) as u8,
+            y: y.max(0.0).min(height as f32 - 1.0) as u8,
+        })
+    }
+}
+
+impl Program<Message> for MatrixView {
+    fn update(
+        &mut self,
+        event: Event,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> (event::Status, Option<Message>) {
+        if !self.drawing || self.image.width() == 0 || self.image.height() == 0 {
+            return (event::Status::Ignored, None);
+        }
+
+        let message = match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => self
+                .led_at(bounds, cursor, false)
+                .map(Message::CanvasPressed),
+            //Once a drag started, leaving the canvas pins the shape to the panel's edge
+            Event::Mouse(mouse::Event::CursorMoved { .. }) if self.dragging => self
+                .led_at(bounds, cursor, true)
+                .map(Message::CanvasDragged),
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) if self.dragging => {
+                self.led_at(bounds, cursor, true)
+                    .map(Message::CanvasReleased)
+            }
+            _ => None,
+        };
+
+        match message {
+            Some(message) => (event::Status::Captured, Some(message)),
+            None => (event::Status::Ignored, None),
+        }
+    }
+
+    fn draw(&self, bounds: Rectangle, _cursor: Cursor) -> Vec<Geometry> {
+        let mut frame = Frame::new(bounds.size());
+        let (width, height) = self.image.dimensions();
+
+        frame.fill_rectangle(Point::ORIGIN, frame.size(), Color::BLACK);
+        if width == 0 || height == 0 {
+            return vec![frame.into_geometry()];
+        }
+
+        let (cell, origin) = self.layout(frame.size());
+        let led = Size::new(cell * (1.0 - LED_GAP), cell * (1.0 - LED_GAP));
+
+        for (x, y, pixel) in self.image.enumerate_pixels() {
+            let [r, g, b] = pixel.0;
+            if r == 0 && g == 0 && b == 0 {
+                continue;
+            }
+
+            frame.fill_rectangle(
+                Point::new(origin.x + x as f32 * cell, origin.y + y as f32 * cell),
+                led,
+                Color::from_rgb8(r, g, b),
+            );
+        }
+
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(&self, bounds: Rectangle, cursor: Cursor) -> mouse::Interaction {
+        if self.drawing && (self.dragging || cursor.is_over(&bounds)) {
+            mouse::Interaction::Crosshair
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
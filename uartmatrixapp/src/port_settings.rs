@@ -0,0 +1,202 @@
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    env, fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use libuartmatrix::Pacing;
+use serialport::SerialPort;
+
+use crate::helper_structs::{DataBits, FlowControl, Parity, StopBits};
+
+/// How a serial port is opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PortSettings {
+    pub baud_rate: u32,
+    pub data_bits: DataBits,
+    pub parity: Parity,
+    pub stop_bits: StopBits,
+    pub flow_control: FlowControl,
+    /// How long to wait for the device to answer a frame.
+    pub read_timeout: Duration,
+}
+
+impl Default for PortSettings {
+    fn default() -> Self {
+        PortSettings {
+            baud_rate: 115200,
+            data_bits: DataBits::Eight,
+            parity: Parity::None,
+            stop_bits: StopBits::One,
+            flow_control: FlowControl::None,
+            read_timeout: Pacing::default().ack_timeout,
+        }
+    }
+}
+
+impl PortSettings {
+    pub fn open(&self, port: &str) -> serialport::Result<Box<dyn SerialPort>> {
+        serialport::new(port, self.baud_rate)
+            .data_bits(self.data_bits.into())
+            .parity(self.parity.into())
+            .stop_bits(self.stop_bits.into())
+            .flow_control(self.flow_control.into())
+            .timeout(self.read_timeout)
+            .open()
+    }
+
+    /// One line of the settings file, without the port name.
+    fn encode(&self) -> String {
+        //The enums are stored as their position in the pickers
+        format!(
+            "{} {} {} {} {} {}",
+            self.baud_rate,
+            index_of(&DataBits::ALL, self.data_bits),
+            index_of(&Parity::ALL, self.parity),
+            index_of(&StopBits::ALL, self.stop_bits),
+            index_of(&FlowControl::ALL, self.flow_control),
+            self.read_timeout.as_millis()
+        )
+    }
+
+    fn decode(line: &str) -> Option<Self> {
+        let fields = line
+            .split(' ')
+            .map(|field| field.parse::<u64>().ok())
+            .collect::<Option<Vec<u64>>>()?;
+        if fields.len() != 6 {
+            return None;
+        }
+
+        let settings = PortSettings {
+            baud_rate: u32::try_from(fields[0]).ok()?,
+            data_bits: *DataBits::ALL.get(fields[1] as usize)?,
+            parity: *Parity::ALL.get(fields[2] as usize)?,
+            stop_bits: *StopBits::ALL.get(fields[3] as usize)?,
+            flow_control: *FlowControl::ALL.get(fields[4] as usize)?,
+            read_timeout: Duration::from_millis(fields[5]),
+        };
+        Some(settings)
+    }
+}
+
+fn index_of<T: PartialEq>(all: &[T], value: T) -> usize {
+    all.iter().position(|v| *v == value).unwrap_or(0)
+}
+
+/// The settings last used for each port, in the user's config directory.
+fn settings_file() -> Option<PathBuf> {
+    let config = env::var_os("XDG_CONFIG_HOME")
+        .or_else(|| env::var_os("APPDATA"))
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+
+    Some(config.join("uartmatrix").join("ports"))
+}
+
+/// Loads the remembered settings, lines that cannot be read are skipped.
+pub fn load() -> HashMap<String, PortSettings> {
+    match settings_file().and_then(|file| fs::read_to_string(file).ok()) {
+        Some(content) => parse_file(&content),
+        None => HashMap::new(),
+    }
+}
+
+pub fn save(settings: &HashMap<String, PortSettings>) -> io::Result<()> {
+    let file = settings_file()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+    if let Some(directory) = file.parent() {
+        fs::create_dir_all(directory)?;
+    }
+
+    fs::write(file, format_file(settings))
+}
+
+/// One line per port, the port name and its settings separated by a tab.
+fn parse_file(content: &str) -> HashMap<String, PortSettings> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (port, settings) = line.split_once('\t')?;
+            Some((port.to_string(), PortSettings::decode(settings)?))
+        })
+        .collect()
+}
+
+fn format_file(settings: &HashMap<String, PortSettings>) -> String {
+    let mut content = String::new();
+    for (port, port_settings) in settings {
+        content.push_str(&format!("{}\t{}\n", port, port_settings.encode()));
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom() -> PortSettings {
+        PortSettings {
+            baud_rate: 9600,
+            data_bits: DataBits::Seven,
+            parity: Parity::Even,
+            stop_bits: StopBits::Two,
+            flow_control: FlowControl::Hardware,
+            read_timeout: Duration::from_millis(250),
+        }
+    }
+
+    #[test]
+    fn settings_round_trip() {
+        for settings in [PortSettings::default(), custom()] {
+            assert_eq!(PortSettings::decode(&settings.encode()), Some(settings));
+        }
+
+        //Every choice of every picker survives
+        for (i, data_bits) in DataBits::ALL.iter().enumerate() {
+            let settings = PortSettings {
+                data_bits: *data_bits,
+                parity: Parity::ALL[i % Parity::ALL.len()],
+                stop_bits: StopBits::ALL[i % StopBits::ALL.len()],
+                flow_control: FlowControl::ALL[i % FlowControl::ALL.len()],
+                ..PortSettings::default()
+            };
+            assert_eq!(PortSettings::decode(&settings.encode()), Some(settings));
+        }
+    }
+
+    #[test]
+    fn file_round_trip() {
+        let mut settings = HashMap::new();
+        settings.insert("/dev/ttyUSB0".to_string(), PortSettings::default());
+        settings.insert("COM3".to_string(), custom());
+        settings.insert("/dev/serial/by-id/usb-Matrix 1".to_string(), custom());
+
+        assert_eq!(parse_file(&format_file(&settings)), settings);
+        assert!(parse_file("").is_empty());
+    }
+
+    #[test]
+    fn skips_malformed_lines() {
+        let valid = format!("COM3\t{}", custom().encode());
+        let content = [
+            "/dev/ttyUSB0 115200 3 0 0 0 100",
+            "/dev/ttyUSB1\t115200 3 0 0",
+            "/dev/ttyUSB2\t115200 3 0 0 0 100 7",
+            "/dev/ttyUSB3\t115200 9 0 0 0 100",
+            "/dev/ttyUSB4\tfast 3 0 0 0 100",
+            "/dev/ttyUSB5\t115200 3 0 0 0 -1",
+            "/dev/ttyUSB7\t4294967296 3 0 0 0 100",
+            "/dev/ttyUSB6\t",
+            "",
+            &valid,
+        ]
+        .join("\n");
+
+        let settings = parse_file(&content);
+        assert_eq!(settings.len(), 1);
+        assert_eq!(settings["COM3"], custom());
+    }
+}
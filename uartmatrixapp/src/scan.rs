@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use libuartmatrix::{DeviceParams, MatrixClient, Pacing, Transport};
+
+use crate::port_settings::PortSettings;
+
+//Ports without a matrix never answer, so each one costs this long per attempt
+const PROBE_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// A serial port in the port list, with the matrix that answered on it if it was scanned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Port {
+    pub name: String,
+    pub device: Option<DeviceParams>,
+}
+
+impl Port {
+    pub fn new(name: String) -> Self {
+        Port { name, device: None }
+    }
+}
+
+impl std::fmt::Display for Port {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.device {
+            Some(params) => {
+                let (major, minor, patch) = params.firmware_version;
+                write!(
+                    f,
+                    "{} ({}x{} matrix, firmware {}.{}.{})",
+                    self.name, params.width, params.height, major, minor, patch
+                )
+            }
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// Pings `port` and asks for its parameters, `None` unless both get a valid reply.
+pub fn probe(port: &str, settings: &PortSettings) -> Option<DeviceParams> {
+    let transport: Box<dyn Transport> = Box::new(settings.open(port).ok()?);
+    let pacing = Pacing {
+        ack_timeout: PROBE_TIMEOUT,
+        retries: 0,
+        ..Pacing::default()
+    };
+    let mut client = MatrixClient::with_pacing(transport, pacing);
+
+    client.ping().ok()?;
+    client.params().ok()
+}
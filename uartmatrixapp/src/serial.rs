@@ -1,29 +1,60 @@
-use std::{thread, time};
-
 use image::RgbImage;
-use serialport::SerialPort;
 
 use libuartmatrix::enums::DisplayMode;
 use libuartmatrix::*;
+use uartmatrixemu::Emulator;
 
 use crate::helper_structs::{Animation, Direction, Font};
+use crate::worker::{Job, Worker};
+
+/// Where frames go: the local preview right away, the device through the worker.
+pub struct Link<'a> {
+    worker: &'a Worker,
+    preview: &'a mut Emulator,
+}
+
+impl<'a> Link<'a> {
+    pub fn new(worker: &'a Worker, preview: &'a mut Emulator) -> Self {
+        Link { worker, preview }
+    }
+
+    pub fn send(&mut self, frame: &Frame) {
+        self.queue(frame, None);
+    }
+
+    /// Like `send`, but failures are reported as being about `label`.
+    pub fn send_labelled(&mut self, frame: &Frame, label: String) {
+        self.queue(frame, Some(label));
+    }
+
+    fn queue(&mut self, frame: &Frame, label: Option<String>) {
+        //The preview rejects the same frames the device would, so its replies are not needed
+        self.preview.feed(frame);
+        self.worker.send(Job::Send {
+            frame: Box::new(frame.clone()),
+            label,
+        });
+    }
+}
+
+/// Sends every non-empty row, the error is about the first row that could not be encoded.
+pub fn send_text(link: &mut Link, text_rows: &[String]) -> Result<(), String> {
+    let mut result = Ok(());
 
-pub fn send_text(port: &mut dyn SerialPort, text_rows: &[String]) {
     for (i, row) in text_rows.iter().enumerate() {
         if row.is_empty() {
             continue;
         }
-        let result = serialize_write_line(i as u8, row.as_str());
-        if let Some(packet) = result {
-            let _result = port.write(&packet);
-            let mut buffer = [0; 20];
-            let _result = port.read(&mut buffer);
-            thread::sleep(time::Duration::from_millis(20));
+        match serialize_write_line(i as u8, row.as_str()) {
+            Ok(packet) => link.send_labelled(&packet, format!("Row {}", i)),
+            Err(e) => result = result.and(Err(format!("Row {}: {}", i, e))),
         }
     }
+
+    result
 }
 
-pub fn send_colors(port: &mut dyn SerialPort, color_rows: &[RgbColor]) {
+pub fn send_colors(link: &mut Link, color_rows: &[RgbColor]) {
     for (i, row) in color_rows.iter().enumerate() {
         let result = serialize_set_color(
             i as u8,
@@ -33,15 +64,14 @@ pub fn send_colors(port: &mut dyn SerialPort, color_rows: &[RgbColor]) {
                 b: row.b,
             },
         );
-        if let Some(packet) = result {
-            let _result = port.write(&packet);
-            thread::sleep(time::Duration::from_millis(20));
+        if let Ok(packet) = result {
+            link.send(&packet);
         }
     }
 }
 
 pub fn send_animations(
-    port: &mut dyn SerialPort,
+    link: &mut Link,
     animations: &[Option<Animation>],
     animation_speeds: &[String],
     animation_directions: &[Option<Direction>],
@@ -52,81 +82,70 @@ pub fn send_animations(
         .zip(animation_directions)
         .enumerate()
     {
-        if anim.is_none() {
-            continue;
-        }
-
-        let animation = match anim.unwrap() {
-            Animation::Blink => {
-                let speed = str::parse::<u8>(speed);
-                if speed.is_err() {
-                    continue;
-                }
-                let speed = 60 / speed.unwrap();
-
-                libuartmatrix::enums::Animation::BlinkAnimation(speed)
-            }
-            Animation::Slide => {
-                let speed = str::parse::<u8>(speed);
-                
-                if speed.is_err() {
-                    continue;
-                }
-
-                let speed = 60 / speed.unwrap();
-
-                if direction.is_none() {
-                    continue;
-                }
-
-                let dir = match direction.unwrap() {
-                    Direction::Left => libuartmatrix::enums::Direction::Left,
-                    Direction::Right => libuartmatrix::enums::Direction::Right,
-                };
-
-                libuartmatrix::enums::Animation::SlideAnimation(speed, dir)
-            }
-            Animation::None => libuartmatrix::enums::Animation::NoAnimation,
+        let animation = match protocol_animation(*anim, speed, *direction) {
+            Some(animation) => animation,
+            None => continue,
         };
 
         let result = serialize_set_animation(i as u8, animation);
-        if let Some(packet) = result {
-            let _result = port.write(&packet);
-            thread::sleep(time::Duration::from_millis(20));
+        if let Ok(packet) = result {
+            link.send(&packet);
         }
     }
 }
 
-pub fn send_fonts(port: &mut dyn SerialPort, fonts: &[Option<Font>]) {
+/// Converts the animation settings of a row, `None` while they are incomplete or invalid.
+pub fn protocol_animation(
+    animation: Option<Animation>,
+    speed: &str,
+    direction: Option<Direction>,
+) -> Option<enums::Animation> {
+    let animation = match animation? {
+        Animation::Blink => {
+            let speed = 60u8.checked_div(str::parse::<u8>(speed).ok()?)?;
+
+            enums::Animation::BlinkAnimation(speed)
+        }
+        Animation::Slide => {
+            let speed = 60u8.checked_div(str::parse::<u8>(speed).ok()?)?;
+
+            let dir = match direction? {
+                Direction::Left => enums::Direction::Left,
+                Direction::Right => enums::Direction::Right,
+            };
+
+            enums::Animation::SlideAnimation(speed, dir)
+        }
+        Animation::None => enums::Animation::NoAnimation,
+    };
+
+    Some(animation)
+}
+
+pub fn send_fonts(link: &mut Link, fonts: &[Option<Font>]) {
     for (i, row_font) in fonts.iter().enumerate() {
         if row_font.is_none() {
             continue;
         }
 
-        let font = match row_font.unwrap() {
-            Font::Default => libuartmatrix::enums::FontType::Default,
-            Font::Ibm => libuartmatrix::enums::FontType::Ibm,
-            Font::Pro => libuartmatrix::enums::FontType::Pro,
-        };
+        let font: libuartmatrix::enums::FontType = row_font.unwrap().into();
 
         let result = serialize_set_font(i as u8, font.into());
 
-        if let Some(packet) = result {
-            let _result = port.write(&packet);
-            thread::sleep(time::Duration::from_millis(20));
+        if let Ok(packet) = result {
+            link.send(&packet);
         }
     }
 }
 
-pub fn send_change_mode(port: &mut dyn SerialPort, mode: DisplayMode) {
+pub fn send_change_mode(link: &mut Link, mode: DisplayMode) {
     let result = serialize_switch_mode(mode);
-    if let Some(packet) = result {
-        let _result = port.write(&packet);
-        thread::sleep(time::Duration::from_millis(20));
+    if let Ok(packet) = result {
+        link.send(&packet);
     }
 }
 
-pub fn send_image(port: &mut dyn SerialPort, image: RgbImage) {
+pub fn send_image(link: &mut Link, image: RgbImage) {
     for (i, row) in image.rows().enumerate() {
         let mut row_vec: Vec<(u8, u8, u8)> = Vec::new();
         for pixel in row {
@@ -136,14 +155,13 @@ pub fn send_image(port: &mut dyn SerialPort, image: RgbImage) {
         }
 
         let result = serialize_draw_row(i as u8, row_vec);
-        if let Some(packet) = result {
-            let _result = port.write(&packet);
-            thread::sleep(time::Duration::from_millis(50));
+        if let Ok(packet) = result {
+            link.send(&packet);
         }
     }
 }
 
-pub fn send_draw_pixel(port: &mut dyn SerialPort, x: &str, y: &str, color: &RgbColor) {
+pub fn send_draw_pixel(link: &mut Link, x: &str, y: &str, color: &RgbColor) {
     let x: Result<u8, _> = x.parse();
     let y: Result<u8, _> = y.parse();
 
@@ -158,14 +176,13 @@ pub fn send_draw_pixel(port: &mut dyn SerialPort, x: &str, y: &str, color: &RgbC
         },
         *color,
     );
-    if let Some(packet) = result {
-        let _result = port.write(&packet);
-        thread::sleep(time::Duration::from_millis(50));
+    if let Ok(packet) = result {
+        link.send(&packet);
     }
 }
 
 pub fn send_draw_line(
-    port: &mut dyn SerialPort,
+    link: &mut Link,
     x_1: &str,
     y_1: &str,
     x_2: &str,
@@ -196,14 +213,13 @@ pub fn send_draw_line(
         *color,
     );
 
-    if let Some(packet) = result {
-        let _result = port.write(&packet);
-        thread::sleep(time::Duration::from_millis(50));
+    if let Ok(packet) = result {
+        link.send(&packet);
     }
 }
 
 pub fn send_draw_rectangle(
-    port: &mut dyn SerialPort,
+    link: &mut Link,
     x_1: &str,
     y_1: &str,
     x_2: &str,
@@ -236,14 +252,13 @@ pub fn send_draw_rectangle(
         filled,
     );
 
-    if let Some(packet) = result {
-        let _result = port.write(&packet);
-        thread::sleep(time::Duration::from_millis(50));
+    if let Ok(packet) = result {
+        link.send(&packet);
     }
 }
 
 pub fn send_draw_triangle(
-    port: &mut dyn SerialPort,
+    link: &mut Link,
     x_1: &str,
     y_1: &str,
     x_2: &str,
@@ -291,14 +306,13 @@ pub fn send_draw_triangle(
         filled,
     );
 
-    if let Some(packet) = result {
-        let _result = port.write(&packet);
-        thread::sleep(time::Duration::from_millis(50));
+    if let Ok(packet) = result {
+        link.send(&packet);
     }
 }
 
 pub fn send_draw_circle(
-    port: &mut dyn SerialPort,
+    link: &mut Link,
     x_1: &str,
     y_1: &str,
     radius: &str,
@@ -326,17 +340,23 @@ pub fn send_draw_circle(
         filled,
     );
 
-    if let Some(packet) = result {
-        let _result = port.write(&packet);
-        thread::sleep(time::Duration::from_millis(50));
+    if let Ok(packet) = result {
+        link.send(&packet);
+    }
+}
+
+pub fn send_command(link: &mut Link, command: &Command) {
+    let result = serialize_command(command);
+
+    if let Ok(packet) = result {
+        link.send(&packet);
     }
 }
 
-pub fn send_clear_screen(port: &mut dyn SerialPort) {
+pub fn send_clear_screen(link: &mut Link) {
     let result = serialize_clear();
 
-    if let Some(packet) = result {
-        let _result = port.write(&packet);
-        thread::sleep(time::Duration::from_millis(50));
+    if let Ok(packet) = result {
+        link.send(&packet);
     }
 }
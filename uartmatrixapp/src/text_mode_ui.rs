@@ -1,107 +1,179 @@
 use std::ops::RangeInclusive;
 
-use iced::{Button, Canvas, Column, Length, PickList, Row, Slider, Text, TextInput};
+use iced::{Button, Canvas, Color, Column, Length, PickList, Row, Slider, Text, TextInput};
 
-use libuartmatrix::RgbColor;
+use libuartmatrix::enums::FontType;
+use libuartmatrix::{DeviceParams, RgbColor};
+use uartmatrixdevice::MatrixBackend;
+use uartmatrixemu::MatrixState;
 
 use crate::{
     helper_structs::{Animation, Direction, Font, RgbSlidersState},
+    matrix_view::MatrixView,
     rect::Rect,
+    serial::protocol_animation,
     Message,
 };
 
 pub const COLOR_SLIDER_RANGE: RangeInclusive<i32> = 0..=255;
 
+const WARNING_COLOR: Color = Color::from_rgb(0.8, 0.5, 0.0);
+const ERROR_COLOR: Color = Color::from_rgb(0.8, 0.0, 0.0);
+
+/// How a row's text fits on the panel with the font selected for that row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextFit {
+    pub width: u32,
+    pub panel_width: u32,
+    pub sliding: bool,
+    pub unsupported: Option<char>,
+}
+
+impl TextFit {
+    /// Text wider than the panel is cut off unless it slides across it.
+    pub fn overflows(&self) -> bool {
+        !self.sliding && self.width > self.panel_width
+    }
+}
+
 pub struct TextModeData {
+    pub panel_width: u32,
+
     pub send_colors_btn: iced::button::State,
     pub send_anims_btn: iced::button::State,
     pub send_text_btn: iced::button::State,
     pub send_fonts_btn: iced::button::State,
-    pub anim_select_states: [iced::pick_list::State<Animation>; 3],
-    pub anim_select_values: [Option<Animation>; 3],
+    pub anim_select_states: Vec<iced::pick_list::State<Animation>>,
+    pub anim_select_values: Vec<Option<Animation>>,
 
-    pub anim_speed_states: [iced::text_input::State; 3],
-    pub anim_speed_values: [String; 3],
+    pub anim_speed_states: Vec<iced::text_input::State>,
+    pub anim_speed_values: Vec<String>,
 
-    pub anim_direction_states: [iced::pick_list::State<Direction>; 3],
-    pub anim_direction_values: [Option<Direction>; 3],
+    pub anim_direction_states: Vec<iced::pick_list::State<Direction>>,
+    pub anim_direction_values: Vec<Option<Direction>>,
 
-    pub font_states: [iced::pick_list::State<Font>; 3],
-    pub font_values: [Option<Font>; 3],
+    pub available_fonts: Vec<Font>,
+    pub font_states: Vec<iced::pick_list::State<Font>>,
+    pub font_values: Vec<Option<Font>>,
 
-    pub text_rows_states: [iced::text_input::State; 3],
-    pub text_rows_values: [String; 3],
+    pub text_rows_states: Vec<iced::text_input::State>,
+    pub text_rows_values: Vec<String>,
 
-    pub color_slider_states: [RgbSlidersState; 3],
-    pub color_slider_values: [RgbColor; 3],
+    pub color_slider_states: Vec<RgbSlidersState>,
+    pub color_slider_values: Vec<RgbColor>,
 }
 
 impl TextModeData {
-    pub fn new() -> Self {
+    pub fn new(params: &DeviceParams) -> Self {
+        let rows = params.text_rows as usize;
+        let available_fonts: Vec<Font> = params.fonts().map(Font::from).collect();
+        let default_font = available_fonts.first().copied();
+
         TextModeData {
+            panel_width: params.width as u32,
+
             send_colors_btn: iced::button::State::new(),
             send_text_btn: iced::button::State::new(),
             send_anims_btn: iced::button::State::new(),
             send_fonts_btn: iced::button::State::new(),
 
-            anim_select_states: [
-                iced::pick_list::State::default(),
-                iced::pick_list::State::default(),
-                iced::pick_list::State::default(),
-            ],
-            anim_select_values: [
-                Some(Animation::None),
-                Some(Animation::None),
-                Some(Animation::None),
-            ],
-
-            anim_speed_states: [
-                iced::text_input::State::new(),
-                iced::text_input::State::new(),
-                iced::text_input::State::new(),
-            ],
-            anim_speed_values: [String::from(""), String::from(""), String::from("")],
-
-            anim_direction_states: [
-                iced::pick_list::State::default(),
-                iced::pick_list::State::default(),
-                iced::pick_list::State::default(),
-            ],
-            anim_direction_values: [Some(Direction::Left); 3],
-
-            font_states: [
-                iced::pick_list::State::default(),
-                iced::pick_list::State::default(),
-                iced::pick_list::State::default(),
-            ],
-            font_values: [Some(Font::Default); 3],
-
-            text_rows_states: [
-                iced::text_input::State::new(),
-                iced::text_input::State::new(),
-                iced::text_input::State::new(),
-            ],
-
-            text_rows_values: [String::from(""), String::from(""), String::from("")],
-
-            color_slider_states: [RgbSlidersState::new(); 3],
-            color_slider_values: [RgbColor::new(); 3],
+            anim_select_states: (0..rows)
+                .map(|_| iced::pick_list::State::default())
+                .collect(),
+            anim_select_values: vec![Some(Animation::None); rows],
+
+            anim_speed_states: (0..rows).map(|_| iced::text_input::State::new()).collect(),
+            anim_speed_values: vec![String::from(""); rows],
+
+            anim_direction_states: (0..rows)
+                .map(|_| iced::pick_list::State::default())
+                .collect(),
+            anim_direction_values: vec![Some(Direction::Left); rows],
+
+            available_fonts,
+            font_states: (0..rows)
+                .map(|_| iced::pick_list::State::default())
+                .collect(),
+            font_values: vec![default_font; rows],
+
+            text_rows_states: (0..rows).map(|_| iced::text_input::State::new()).collect(),
+            text_rows_values: vec![String::from(""); rows],
+
+            color_slider_states: vec![RgbSlidersState::new(); rows],
+            color_slider_values: vec![RgbColor::new(); rows],
+        }
+    }
+
+    pub fn font(&self, row: usize) -> FontType {
+        self.font_values[row].unwrap_or_default().into()
+    }
+
+    pub fn text_fit(&self, row: usize) -> TextFit {
+        let text = &self.text_rows_values[row];
+        let font = self.font(row).bitmap();
+
+        TextFit {
+            width: font.text_width(text),
+            panel_width: self.panel_width,
+            sliding: self.anim_select_values[row] == Some(Animation::Slide),
+            unsupported: font.unsupported_char(text),
+        }
+    }
+
+    /// Text can only be sent once every row can be drawn with its font.
+    pub fn text_is_sendable(&self) -> bool {
+        (0..self.text_rows_values.len()).all(|row| self.text_fit(row).unsupported.is_none())
+    }
+
+    /// Applies what the send buttons would transmit, to preview rows before sending them.
+    pub fn apply_to(&self, state: &mut MatrixState) {
+        for i in 0..self.text_rows_values.len().min(state.rows().len()) {
+            let row = i as u8;
+
+            //Rows the send buttons skip keep what the device is showing
+            if !self.text_rows_values[i].is_empty() {
+                let _ = state.write_line(row, &self.text_rows_values[i]);
+            }
+            if self.font_values[i].is_some() {
+                let _ = state.set_font(row, self.font(i));
+            }
+            let _ = state.set_color(row, self.color_slider_values[i]);
+
+            let animation = protocol_animation(
+                self.anim_select_values[i],
+                &self.anim_speed_values[i],
+                self.anim_direction_values[i],
+            );
+            if let Some(animation) = animation {
+                let _ = state.set_animation(row, animation);
+            }
         }
     }
 }
 
-pub fn add_text_mode_ui(data: &mut TextModeData) -> (Column<Message>, Column<Message>) {
+pub fn add_text_mode_ui(
+    data: &mut TextModeData,
+    preview: Canvas<Message, MatrixView>,
+) -> (Column<Message>, Column<Message>) {
     let mut left_column = Column::new().max_width(600).spacing(20);
-    let mut right_column = Column::new().max_width(600).spacing(20);
+    let mut right_column = Column::new().max_width(600).spacing(20).push(preview);
+
+    let fits: Vec<TextFit> = (0..data.text_rows_values.len())
+        .map(|row| data.text_fit(row))
+        .collect();
+    let text_is_sendable = data.text_is_sendable();
 
     left_column = add_text_section(
         left_column,
         &mut data.text_rows_states,
         &mut data.text_rows_values,
+        &fits,
     );
 
-    let send_text_button =
-        Button::new(&mut data.send_text_btn, Text::new("Send text")).on_press(Message::SendText);
+    let mut send_text_button = Button::new(&mut data.send_text_btn, Text::new("Send text"));
+    if text_is_sendable {
+        send_text_button = send_text_button.on_press(Message::SendText);
+    }
 
     left_column = left_column.push(send_text_button);
 
@@ -131,7 +203,12 @@ pub fn add_text_mode_ui(data: &mut TextModeData) -> (Column<Message>, Column<Mes
 
     right_column = right_column.push(send_anims_button);
 
-    right_column = add_font_section(right_column, &mut data.font_states, &mut data.font_values);
+    right_column = add_font_section(
+        right_column,
+        &data.available_fonts,
+        &mut data.font_states,
+        &mut data.font_values,
+    );
 
     let send_fonts_button =
         Button::new(&mut data.send_fonts_btn, Text::new("Set fonts")).on_press(Message::SendFonts);
@@ -143,35 +220,47 @@ pub fn add_text_mode_ui(data: &mut TextModeData) -> (Column<Message>, Column<Mes
 
 fn add_text_section<'a>(
     mut content: Column<'a, Message>,
-    states: &'a mut [iced::text_input::State; 3],
-    strings: &'a mut [String; 3],
+    states: &'a mut [iced::text_input::State],
+    strings: &'a mut [String],
+    fits: &[TextFit],
 ) -> Column<'a, Message> {
     content = content.push(Text::new("Text input:"));
 
-    let [state_1, state_2, state_3] = states;
-
-    let text_field_1 = TextInput::new(state_1, "Row 1 text...", &strings[0], |content| {
-        Message::TextChanged(content, 0)
-    });
-    let text_field_2 = TextInput::new(state_2, "Row 2 text...", &strings[1], |content| {
-        Message::TextChanged(content, 1)
-    });
-    let text_field_3 = TextInput::new(state_3, "Row 3 text...", &strings[2], |content| {
-        Message::TextChanged(content, 2)
-    });
+    for (i, (state, string)) in states.iter_mut().zip(strings.iter()).enumerate() {
+        let placeholder = String::from("Row ") + &(i + 1).to_string() + " text...";
+        let text_field = TextInput::new(state, &placeholder, string, move |content| {
+            Message::TextChanged(content, i)
+        });
 
-    content = content
-        .push(text_field_1)
-        .push(text_field_2)
-        .push(text_field_3);
+        content = content.push(text_field).push(create_fit_text(&fits[i]));
+    }
 
     content
 }
 
+fn create_fit_text(fit: &TextFit) -> Text {
+    let measurement = fit.width.to_string() + " / " + &fit.panel_width.to_string() + " px";
+
+    let text = if let Some(c) = fit.unsupported {
+        Text::new(format!(
+            "{}, '{}' is not available in this font",
+            measurement, c
+        ))
+        .color(ERROR_COLOR)
+    } else if fit.overflows() {
+        Text::new(measurement + ", overflows the panel, use Slide to show all of it")
+            .color(WARNING_COLOR)
+    } else {
+        Text::new(measurement)
+    };
+
+    text.size(16)
+}
+
 fn add_slider_section<'a>(
     mut content: Column<'a, Message>,
-    slider_states: &'a mut [RgbSlidersState; 3],
-    color_values: &'a mut [RgbColor; 3],
+    slider_states: &'a mut [RgbSlidersState],
+    color_values: &'a mut [RgbColor],
 ) -> Column<'a, Message> {
     content = content.push(Text::new("Color selection:"));
 
@@ -226,49 +315,33 @@ fn add_slider_section<'a>(
 
 fn add_anim_section<'a>(
     mut content: Column<'a, Message>,
-    anim_states: &'a mut [iced::pick_list::State<Animation>; 3],
-    anim_values: &'a mut [Option<Animation>; 3],
-    speed_states: &'a mut [iced::text_input::State; 3],
-    speed_values: &'a mut [String; 3],
-    direction_states: &'a mut [iced::pick_list::State<Direction>; 3],
-    direction_values: &'a mut [Option<Direction>; 3],
+    anim_states: &'a mut [iced::pick_list::State<Animation>],
+    anim_values: &'a mut [Option<Animation>],
+    speed_states: &'a mut [iced::text_input::State],
+    speed_values: &'a mut [String],
+    direction_states: &'a mut [iced::pick_list::State<Direction>],
+    direction_values: &'a mut [Option<Direction>],
 ) -> Column<'a, Message> {
     content = content.push(Text::new("Animation selection:"));
 
-    let [state_1, state_2, state_3] = anim_states;
-    let [s_state_1, s_state_2, s_state_3] = speed_states;
-    let [d_state_1, d_state_2, d_state_3] = direction_states;
-
-    content = create_single_anim_section(
-        content,
-        0,
-        state_1,
-        anim_values[0],
-        s_state_1,
-        speed_values[0].clone(),
-        d_state_1,
-        direction_values[0],
-    );
-    content = create_single_anim_section(
-        content,
-        1,
-        state_2,
-        anim_values[1],
-        s_state_2,
-        speed_values[1].clone(),
-        d_state_2,
-        direction_values[1],
-    );
-    content = create_single_anim_section(
-        content,
-        2,
-        state_3,
-        anim_values[2],
-        s_state_3,
-        speed_values[2].clone(),
-        d_state_3,
-        direction_values[2],
-    );
+    let rows = anim_states
+        .iter_mut()
+        .zip(speed_states.iter_mut())
+        .zip(direction_states.iter_mut())
+        .enumerate();
+
+    for (i, ((anim_state, speed_state), direction_state)) in rows {
+        content = create_single_anim_section(
+            content,
+            i,
+            anim_state,
+            anim_values[i],
+            speed_state,
+            speed_values[i].clone(),
+            direction_state,
+            direction_values[i],
+        );
+    }
 
     content
 }
@@ -338,15 +411,16 @@ fn create_single_anim_section<'a>(
 
 fn add_font_section<'a>(
     mut content: Column<'a, Message>,
-    font_states: &'a mut [iced::pick_list::State<Font>; 3],
-    font_values: &'a mut [Option<Font>; 3],
+    available_fonts: &'a [Font],
+    font_states: &'a mut [iced::pick_list::State<Font>],
+    font_values: &'a mut [Option<Font>],
 ) -> Column<'a, Message> {
     content = content.push(Text::new("Font selection:"));
 
     for (i, (state, value)) in font_states.iter_mut().zip(font_values).enumerate() {
         let mut row = Row::new().spacing(20);
 
-        let font_picker = PickList::new(state, &Font::ALL[..], *value, move |value| {
+        let font_picker = PickList::new(state, available_fonts, *value, move |value| {
             Message::FontChanged(value, i)
         });
 
@@ -0,0 +1,194 @@
+use std::{
+    convert::TryFrom,
+    hash::{Hash, Hasher},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
+use iced::futures::{
+    channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+    stream::{self, BoxStream, StreamExt},
+};
+use libuartmatrix::enums::Opcode;
+use libuartmatrix::{AckMode, DeviceParams, Frame, MatrixClient, Pacing, Transport, FRAME_HEADER};
+
+use crate::port_settings::PortSettings;
+use crate::scan::{self, Port};
+
+type Client = MatrixClient<Box<dyn Transport + Send>>;
+
+/// Work for the serial thread, executed in the order it was queued.
+#[derive(Debug)]
+pub enum Job {
+    Connect {
+        port: String,
+        settings: PortSettings,
+    },
+    Disconnect,
+    /// Looks for a matrix on each of the ports, opened with the given settings.
+    Scan(Vec<(String, PortSettings)>),
+    /// Delivers a frame, failures are reported with `label` or else the opcode.
+    Send {
+        frame: Box<Frame>,
+        label: Option<String>,
+    },
+}
+
+/// What the serial thread reports back to the UI.
+#[derive(Debug, Clone)]
+pub enum WorkerEvent {
+    /// `params` is `None` if the device did not report its parameters.
+    Connected {
+        port: String,
+        params: Option<DeviceParams>,
+    },
+    ConnectFailed(String),
+    Failed {
+        label: String,
+        error: String,
+    },
+    /// Every scanned port, with the matrix that answered on it.
+    Scanned(Vec<Port>),
+    /// The device never acknowledged a frame, so they are paced by time from now on.
+    TimedPacing,
+    /// Started working through newly queued jobs.
+    Busy,
+    /// Everything queued so far has been handled.
+    Idle,
+}
+
+/// Owns the serial port on a background thread, so that the UI never waits for it.
+pub struct Worker {
+    jobs: mpsc::Sender<Job>,
+    events: Arc<Mutex<Option<UnboundedReceiver<WorkerEvent>>>>,
+}
+
+impl Worker {
+    pub fn spawn(pacing: Pacing) -> Self {
+        let (jobs, job_receiver) = mpsc::channel();
+        let (event_sender, events) = unbounded();
+
+        thread::spawn(move || run(job_receiver, event_sender, pacing));
+
+        Worker {
+            jobs,
+            events: Arc::new(Mutex::new(Some(events))),
+        }
+    }
+
+    pub fn send(&self, job: Job) {
+        //The thread only stops once the worker is dropped
+        let _ = self.jobs.send(job);
+    }
+
+    /// Events of the worker, there is a single stream for its whole lifetime.
+    pub fn subscription(&self) -> iced::Subscription<WorkerEvent> {
+        iced::Subscription::from_recipe(Events(self.events.clone()))
+    }
+}
+
+fn run(jobs: mpsc::Receiver<Job>, events: UnboundedSender<WorkerEvent>, pacing: Pacing) {
+    let mut client: Option<Client> = None;
+    let report = |event| {
+        let _ = events.unbounded_send(event);
+    };
+
+    let mut busy = false;
+
+    loop {
+        let job = match jobs.try_recv() {
+            Ok(job) => job,
+            Err(mpsc::TryRecvError::Empty) => {
+                if busy {
+                    report(WorkerEvent::Idle);
+                    busy = false;
+                }
+                match jobs.recv() {
+                    Ok(job) => job,
+                    Err(_) => break,
+                }
+            }
+            Err(mpsc::TryRecvError::Disconnected) => break,
+        };
+        if !busy {
+            report(WorkerEvent::Busy);
+            busy = true;
+        }
+
+        match job {
+            Job::Connect { port, settings } => {
+                client = None;
+                match settings.open(&port) {
+                    Ok(s_port) => {
+                        let transport: Box<dyn Transport + Send> = Box::new(s_port);
+                        let pacing = Pacing {
+                            ack_timeout: settings.read_timeout,
+                            ..pacing
+                        };
+                        let mut connected = MatrixClient::with_pacing(transport, pacing);
+                        let params = connected.params().ok();
+                        client = Some(connected);
+                        report(WorkerEvent::Connected { port, params });
+                    }
+                    Err(e) => report(WorkerEvent::ConnectFailed(e.to_string())),
+                }
+            }
+            Job::Disconnect => client = None,
+            Job::Scan(ports) => {
+                let ports = ports
+                    .into_iter()
+                    .map(|(name, settings)| Port {
+                        device: scan::probe(&name, &settings),
+                        name,
+                    })
+                    .collect();
+                report(WorkerEvent::Scanned(ports));
+            }
+            Job::Send { frame, label } => {
+                //Offline, frames only go to the preview
+                let client = match client.as_mut() {
+                    Some(client) => client,
+                    None => continue,
+                };
+
+                let was_timed = client.ack_mode() == AckMode::Timed;
+                if let Err(error) = client.send_frame(&frame) {
+                    report(WorkerEvent::Failed {
+                        label: label.unwrap_or_else(|| describe(&frame)),
+                        error: error.to_string(),
+                    });
+                }
+                if !was_timed && client.ack_mode() == AckMode::Timed {
+                    report(WorkerEvent::TimedPacing);
+                }
+            }
+        }
+    }
+}
+
+fn describe(frame: &Frame) -> String {
+    //The opcode is the first payload byte, right after the header and the length
+    match Opcode::try_from(frame[FRAME_HEADER.len() + 2]) {
+        Ok(opcode) => format!("{:?}", opcode),
+        Err(_) => String::from("Frame"),
+    }
+}
+
+struct Events(Arc<Mutex<Option<UnboundedReceiver<WorkerEvent>>>>);
+
+impl<H: Hasher, I> iced_native::subscription::Recipe<H, I> for Events {
+    type Output = WorkerEvent;
+
+    fn hash(&self, state: &mut H) {
+        std::any::TypeId::of::<Self>().hash(state);
+    }
+
+    fn stream(self: Box<Self>, _input: BoxStream<'static, I>) -> BoxStream<'static, WorkerEvent> {
+        //iced keeps the stream running as long as the hash stays the same, so the
+        //receiver is only taken once
+        match self.0.lock().unwrap().take() {
+            Some(events) => events.boxed(),
+            None => stream::empty().boxed(),
+        }
+    }
+}
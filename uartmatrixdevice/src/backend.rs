@@ -0,0 +1,108 @@
+use libuartmatrix::enums::{Animation, DisplayMode, FontType};
+use libuartmatrix::raster;
+use libuartmatrix::{DeviceParams, NackReason, Point, RgbColor};
+
+/// Hardware side of a UMX device.
+///
+/// The engine validates rows, coordinates and fonts against `params` before calling
+/// into the backend, so implementations only see arguments that fit the panel. Returning
+/// an error makes the engine NACK the command with that reason.
+pub trait MatrixBackend {
+    fn params(&self) -> DeviceParams;
+
+    fn switch_mode(&mut self, mode: DisplayMode) -> Result<(), NackReason>;
+
+    fn write_line(&mut self, row: u8, text: &str) -> Result<(), NackReason>;
+
+    fn set_font(&mut self, row: u8, font: FontType) -> Result<(), NackReason>;
+
+    fn set_color(&mut self, row: u8, color: RgbColor) -> Result<(), NackReason>;
+
+    fn set_animation(&mut self, row: u8, animation: Animation) -> Result<(), NackReason>;
+
+    fn set_pixel(&mut self, position: Point, color: RgbColor) -> Result<(), NackReason>;
+
+    /// Sets the first `pixels.len()` pixels of a framebuffer row.
+    fn draw_row(&mut self, row: u8, pixels: &[RgbColor]) -> Result<(), NackReason>;
+
+    fn clear(&mut self) -> Result<(), NackReason>;
+
+    fn set_output_enabled(&mut self, enabled: bool) -> Result<(), NackReason>;
+
+    //Shapes default to the reference rasterizer drawing through set_pixel, backends
+    //with faster primitives can override them
+    fn draw_line(
+        &mut self,
+        point_1: Point,
+        point_2: Point,
+        thickness: u8,
+        color: RgbColor,
+    ) -> Result<(), NackReason> {
+        plot_shape(self, color, |plot| {
+            raster::line(point_1, point_2, thickness, plot)
+        })
+    }
+
+    fn draw_rectangle(
+        &mut self,
+        point_1: Point,
+        point_2: Point,
+        thickness: u8,
+        color: RgbColor,
+        filled: bool,
+    ) -> Result<(), NackReason> {
+        plot_shape(self, color, |plot| {
+            raster::rectangle(point_1, point_2, thickness, filled, plot)
+        })
+    }
+
+    fn draw_triangle(
+        &mut self,
+        points: [Point; 3],
+        thickness: u8,
+        color: RgbColor,
+        filled: bool,
+    ) -> Result<(), NackReason> {
+        plot_shape(self, color, |plot| {
+            raster::triangle(points, thickness, filled, plot)
+        })
+    }
+
+    fn draw_circle(
+        &mut self,
+        center: Point,
+        radius: u8,
+        thickness: u8,
+        color: RgbColor,
+        filled: bool,
+    ) -> Result<(), NackReason> {
+        plot_shape(self, color, |plot| {
+            raster::circle(center, radius, thickness, filled, plot)
+        })
+    }
+}
+
+//Clips the shape to the panel and stops at the first pixel the backend rejects
+fn plot_shape<B, F>(backend: &mut B, color: RgbColor, draw: F) -> Result<(), NackReason>
+where
+    B: MatrixBackend + ?Sized,
+    F: FnOnce(&mut dyn FnMut(i32, i32)),
+{
+    let params = backend.params();
+    let mut result = Ok(());
+
+    draw(&mut |x, y| {
+        let inside = x >= 0 && y >= 0 && x < params.width as i32 && y < params.height as i32;
+        if inside && result.is_ok() {
+            result = backend.set_pixel(
+                Point {
+                    x: x as u8,
+                    y: y as u8,
+                },
+                color,
+            );
+        }
+    });
+
+    result
+}
@@ -0,0 +1,461 @@
+use libuartmatrix::{
+    serialize_response_into, Command, DeviceParams, FrameDecoder, NackReason, Response, UmxError,
+    FRAME_OVERHEAD, PROTOCOL_VERSION,
+};
+
+use crate::backend::MatrixBackend;
+
+//Opcode plus an encoded DeviceParams is the longest response payload
+const RESPONSE_BUFFER_SIZE: usize = 16 + FRAME_OVERHEAD;
+
+/// Decodes host frames, dispatches them to a backend and produces the replies.
+pub struct ProtocolEngine<B: MatrixBackend> {
+    backend: B,
+    decoder: FrameDecoder,
+}
+
+impl<B: MatrixBackend> ProtocolEngine<B> {
+    pub fn new(backend: B) -> Self {
+        ProtocolEngine {
+            backend,
+            decoder: FrameDecoder::new(),
+        }
+    }
+
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    pub fn backend_mut(&mut self) -> &mut B {
+        &mut self.backend
+    }
+
+    pub fn into_backend(self) -> B {
+        self.backend
+    }
+
+    /// Feeds received bytes and calls `respond` with every response frame to send back.
+    pub fn feed<F: FnMut(&[u8])>(&mut self, data: &[u8], mut respond: F) {
+        let backend = &mut self.backend;
+
+        self.decoder.feed_checked(data, |result| {
+            let response = match result {
+                Ok(payload) => handle_payload(backend, payload),
+                Err(UmxError::CrcMismatch { opcode }) => Response::Nack {
+                    opcode,
+                    reason: NackReason::BadCrc,
+                },
+                Err(_) => return,
+            };
+
+            let mut buffer = [0; RESPONSE_BUFFER_SIZE];
+            if let Ok(length) = serialize_response_into(&response, &mut buffer) {
+                respond(&buffer[..length]);
+            }
+        });
+    }
+
+    /// Handles a single decoded payload and returns the reply for it.
+    pub fn handle_payload(&mut self, payload: &[u8]) -> Response {
+        handle_payload(&mut self.backend, payload)
+    }
+
+    pub fn handle_command(&mut self, command: &Command) -> Response {
+        handle_command(&mut self.backend, command)
+    }
+}
+
+fn handle_payload<B: MatrixBackend>(backend: &mut B, payload: &[u8]) -> Response {
+    let opcode = payload.first().copied().unwrap_or(0);
+
+    match Command::decode(payload) {
+        Ok(command) => handle_command(backend, &command),
+        Err(UmxError::UnknownOpcode(_)) => Response::Nack {
+            opcode,
+            reason: NackReason::BadOpcode,
+        },
+        Err(_) => Response::Nack {
+            opcode,
+            reason: NackReason::BadPayload,
+        },
+    }
+}
+
+fn handle_command<B: MatrixBackend>(backend: &mut B, command: &Command) -> Response {
+    let opcode = command.opcode().into();
+
+    let result = match command {
+        Command::ParamRequest => return Response::ParamReport(backend.params()),
+        //Pings from other protocol versions are answered too, so the host can report the mismatch
+        Command::Ping { .. } => {
+            return Response::Pong {
+                version: PROTOCOL_VERSION,
+            }
+        }
+        _ => validate(&backend.params(), command).and_then(|_| dispatch(backend, command)),
+    };
+
+    match result {
+        Ok(()) => Response::Ack { opcode },
+        Err(reason) => Response::Nack { opcode, reason },
+    }
+}
+
+fn validate(params: &DeviceParams, command: &Command) -> Result<(), NackReason> {
+    let text_row = |row: u8| {
+        if row < params.text_rows {
+            Ok(())
+        } else {
+            Err(NackReason::BadRow)
+        }
+    };
+
+    match command {
+        Command::WriteLine { row, .. }
+        | Command::SetColor { row, .. }
+        | Command::SetAnimation { row, .. } => text_row(*row),
+        Command::SetFont { row, font } => {
+            text_row(*row)?;
+            if params.supports_font(*font) {
+                Ok(())
+            } else {
+                Err(NackReason::BadPayload)
+            }
+        }
+        Command::DrawPixel { position, .. } => {
+            if position.x < params.width && position.y < params.height {
+                Ok(())
+            } else {
+                Err(NackReason::BadPayload)
+            }
+        }
+        Command::DrawRow { row, pixels } => {
+            if *row >= params.height {
+                Err(NackReason::BadRow)
+            } else if pixels.len() > params.width as usize {
+                Err(NackReason::BadPayload)
+            } else {
+                Ok(())
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+fn dispatch<B: MatrixBackend>(backend: &mut B, command: &Command) -> Result<(), NackReason> {
+    match command {
+        Command::SwitchMode(mode) => backend.switch_mode(*mode),
+        Command::WriteLine { row, text } => backend.write_line(*row, text),
+        Command::SetFont { row, font } => backend.set_font(*row, *font),
+        Command::SetColor { row, color } => backend.set_color(*row, *color),
+        Command::SetAnimation { row, animation } => backend.set_animation(*row, *animation),
+        Command::DrawPixel { position, color } => backend.set_pixel(*position, *color),
+        Command::DrawRow { row, pixels } => backend.draw_row(*row, pixels),
+        Command::DrawLine {
+            point_1,
+            point_2,
+            thickness,
+            color,
+        } => backend.draw_line(*point_1, *point_2, *thickness, *color),
+        Command::DrawRectangle {
+            point_1,
+            point_2,
+            thickness,
+            color,
+            filled,
+        } => backend.draw_rectangle(*point_1, *point_2, *thickness, *color, *filled),
+        Command::DrawTriangle {
+            point_1,
+            point_2,
+            point_3,
+            thickness,
+            color,
+            filled,
+        } => backend.draw_triangle([*point_1, *point_2, *point_3], *thickness, *color, *filled),
+        Command::DrawCircle {
+            center,
+            radius,
+            thickness,
+            color,
+            filled,
+        } => backend.draw_circle(*center, *radius, *thickness, *color, *filled),
+        Command::Clear => backend.clear(),
+        Command::EnableOutput => backend.set_output_enabled(true),
+        Command::DisableOutput => backend.set_output_enabled(false),
+        //Answered by the engine itself
+        Command::ParamRequest | Command::Ping { .. } => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libuartmatrix::enums::{Animation, DisplayMode, FontType, Opcode};
+    use libuartmatrix::{serialize_command, Point, RgbColor};
+
+    #[derive(Default)]
+    struct RecordingBackend {
+        calls: Vec<String>,
+        output_enabled: bool,
+    }
+
+    impl MatrixBackend for RecordingBackend {
+        fn params(&self) -> DeviceParams {
+            DeviceParams {
+                font_mask: 0b011,
+                ..DeviceParams::default()
+            }
+        }
+
+        fn switch_mode(&mut self, mode: DisplayMode) -> Result<(), NackReason> {
+            self.calls.push(format!("mode {:?}", mode));
+            Ok(())
+        }
+
+        fn write_line(&mut self, row: u8, text: &str) -> Result<(), NackReason> {
+            self.calls.push(format!("line {} {}", row, text));
+            Ok(())
+        }
+
+        fn set_font(&mut self, row: u8, font: FontType) -> Result<(), NackReason> {
+            self.calls.push(format!("font {} {:?}", row, font));
+            Ok(())
+        }
+
+        fn set_color(&mut self, row: u8, color: RgbColor) -> Result<(), NackReason> {
+            self.calls.push(format!("color {} {:?}", row, color));
+            Ok(())
+        }
+
+        fn set_animation(&mut self, row: u8, animation: Animation) -> Result<(), NackReason> {
+            self.calls
+                .push(format!("animation {} {:?}", row, animation));
+            Ok(())
+        }
+
+        fn set_pixel(&mut self, position: Point, _color: RgbColor) -> Result<(), NackReason> {
+            self.calls
+                .push(format!("pixel {} {}", position.x, position.y));
+            Ok(())
+        }
+
+        fn draw_row(&mut self, row: u8, pixels: &[RgbColor]) -> Result<(), NackReason> {
+            self.calls.push(format!("row {} {}", row, pixels.len()));
+            Ok(())
+        }
+
+        fn clear(&mut self) -> Result<(), NackReason> {
+            //Pretend the framebuffer is locked while the output is running
+            if self.output_enabled {
+                return Err(NackReason::Other(9));
+            }
+            self.calls.push(String::from("clear"));
+            Ok(())
+        }
+
+        fn set_output_enabled(&mut self, enabled: bool) -> Result<(), NackReason> {
+            self.output_enabled = enabled;
+            Ok(())
+        }
+    }
+
+    fn responses(engine: &mut ProtocolEngine<RecordingBackend>, data: &[u8]) -> Vec<Response> {
+        let mut responses = vec![];
+        let mut decoder = FrameDecoder::new();
+        engine.feed(data, |frame| {
+            decoder.feed(frame, |payload| {
+                responses.push(Response::decode(payload).unwrap())
+            })
+        });
+        responses
+    }
+
+    fn command_frames(commands: &[Command]) -> Vec<u8> {
+        commands
+            .iter()
+            .flat_map(|command| serialize_command(command).unwrap().to_vec())
+            .collect()
+    }
+
+    #[test]
+    fn dispatches_commands_and_acks() {
+        let mut engine = ProtocolEngine::new(RecordingBackend::default());
+        let data = command_frames(&[
+            Command::SwitchMode(DisplayMode::Text),
+            Command::WriteLine {
+                row: 1,
+                text: heapless::String::from("HI"),
+            },
+            Command::DrawRow {
+                row: 31,
+                pixels: core::iter::repeat_n(RgbColor::new(), 64).collect(),
+            },
+            Command::Clear,
+        ]);
+
+        let opcodes: Vec<u8> = [
+            Opcode::SwitchMode,
+            Opcode::WriteLine,
+            Opcode::DrawRow,
+            Opcode::Clear,
+        ]
+        .iter()
+        .map(|opcode| (*opcode).into())
+        .collect();
+        assert_eq!(
+            responses(&mut engine, &data),
+            opcodes
+                .into_iter()
+                .map(|opcode| Response::Ack { opcode })
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            engine.backend().calls,
+            vec!["mode Text", "line 1 HI", "row 31 64", "clear"]
+        );
+    }
+
+    #[test]
+    fn answers_ping_and_param_request() {
+        let mut engine = ProtocolEngine::new(RecordingBackend::default());
+        let data = command_frames(&[
+            Command::Ping {
+                version: PROTOCOL_VERSION,
+            },
+            Command::ParamRequest,
+        ]);
+
+        assert_eq!(
+            responses(&mut engine, &data),
+            vec![
+                Response::Pong {
+                    version: PROTOCOL_VERSION
+                },
+                Response::ParamReport(engine.backend().params())
+            ]
+        );
+
+        //A host speaking another protocol version still gets our version back
+        assert_eq!(
+            engine.handle_payload(&[Opcode::Ping.into(), PROTOCOL_VERSION + 1]),
+            Response::Pong {
+                version: PROTOCOL_VERSION
+            }
+        );
+    }
+
+    #[test]
+    fn nacks_invalid_commands() {
+        let mut engine = ProtocolEngine::new(RecordingBackend::default());
+        let nack = |opcode: Opcode, reason| Response::Nack {
+            opcode: opcode.into(),
+            reason,
+        };
+
+        let cases = [
+            (
+                Command::SetColor {
+                    row: 3,
+                    color: RgbColor::new(),
+                },
+                nack(Opcode::SetColor, NackReason::BadRow),
+            ),
+            (
+                Command::SetFont {
+                    row: 0,
+                    font: FontType::Ibm,
+                },
+                nack(Opcode::SetFont, NackReason::BadPayload),
+            ),
+            (
+                Command::DrawPixel {
+                    position: Point { x: 64, y: 0 },
+                    color: RgbColor::new(),
+                },
+                nack(Opcode::DrawPixel, NackReason::BadPayload),
+            ),
+            (
+                Command::DrawRow {
+                    row: 32,
+                    pixels: heapless::Vec::new(),
+                },
+                nack(Opcode::DrawRow, NackReason::BadRow),
+            ),
+        ];
+
+        for (command, expected) in cases.iter() {
+            assert_eq!(&engine.handle_command(command), expected);
+        }
+        assert!(engine.backend().calls.is_empty());
+
+        assert_eq!(
+            engine.handle_payload(&[42]),
+            Response::Nack {
+                opcode: 42,
+                reason: NackReason::BadOpcode
+            }
+        );
+        assert_eq!(
+            engine.handle_payload(&[Opcode::Clear.into(), 1]),
+            nack(Opcode::Clear, NackReason::BadPayload)
+        );
+    }
+
+    #[test]
+    fn rasterizes_shapes_through_set_pixel() {
+        let mut engine = ProtocolEngine::new(RecordingBackend::default());
+        let command = Command::DrawLine {
+            point_1: Point { x: 61, y: 31 },
+            point_2: Point { x: 70, y: 31 },
+            thickness: 1,
+            color: RgbColor::new(),
+        };
+
+        assert_eq!(
+            engine.handle_command(&command),
+            Response::Ack {
+                opcode: Opcode::DrawLine.into()
+            }
+        );
+        assert_eq!(
+            engine.backend().calls,
+            vec!["pixel 61 31", "pixel 62 31", "pixel 63 31"]
+        );
+    }
+
+    #[test]
+    fn forwards_backend_errors() {
+        let mut engine = ProtocolEngine::new(RecordingBackend::default());
+
+        assert_eq!(
+            engine.handle_command(&Command::EnableOutput),
+            Response::Ack {
+                opcode: Opcode::EnableOutput.into()
+            }
+        );
+        assert_eq!(
+            engine.handle_command(&Command::Clear),
+            Response::Nack {
+                opcode: Opcode::Clear.into(),
+                reason: NackReason::Other(9)
+            }
+        );
+    }
+
+    #[test]
+    fn nacks_corrupted_frames() {
+        let mut engine = ProtocolEngine::new(RecordingBackend::default());
+        let mut data = command_frames(&[Command::Clear]);
+        let last = data.len() - 1;
+        data[last] ^= 0xFF;
+
+        assert_eq!(
+            responses(&mut engine, &data),
+            vec![Response::Nack {
+                opcode: Opcode::Clear.into(),
+                reason: NackReason::BadCrc
+            }]
+        );
+        assert!(engine.backend().calls.is_empty());
+    }
+}
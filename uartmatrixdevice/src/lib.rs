@@ -0,0 +1,13 @@
+#![cfg_attr(not(test), no_std)]
+
+//! Device side of the UMX protocol.
+//!
+//! Firmware implements [`MatrixBackend`] for its panel and pushes every received byte
+//! into a [`ProtocolEngine`], which decodes the commands, dispatches them to the backend
+//! and hands back the response frames to send to the host.
+
+pub use backend::MatrixBackend;
+pub use engine::ProtocolEngine;
+
+mod backend;
+mod engine;
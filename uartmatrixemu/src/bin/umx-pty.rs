@@ -0,0 +1,175 @@
+//! Serves an emulated matrix behind a pseudo-terminal.
+//!
+//! Usage: `umx-pty [--size WIDTHxHEIGHT] [--link PATH] [--render]`
+//!
+//! The slave path is printed on startup; open it like any serial port. `--link`
+//! additionally creates a stable symlink to it, e.g. `/tmp/ttyUMX0`. `--render` draws
+//! the panel in the terminal using truecolor escape codes and redraws it after every
+//! command and while animations run.
+
+#[cfg(unix)]
+fn main() {
+    if let Err(e) = pty::run() {
+        eprintln!("umx-pty: {}", e);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("umx-pty: pseudo-terminals are only available on unix");
+    std::process::exit(1);
+}
+
+#[cfg(unix)]
+mod pty {
+    use std::env;
+    use std::fs::{self, File};
+    use std::io::{self, Read, Write};
+    use std::os::unix::io::FromRawFd;
+    use std::path::PathBuf;
+    use std::time::Instant;
+
+    use libuartmatrix::{FrameDecoder, Response};
+    use nix::errno::Errno;
+    use nix::poll::{poll, PollFd, PollFlags};
+    use nix::pty::openpty;
+    use nix::sys::termios::{cfmakeraw, tcgetattr, tcsetattr, SetArg};
+    use nix::unistd::ttyname;
+    use uartmatrixemu::{terminal, Emulator};
+
+    //About 30 redraws per second while an animation is running
+    const FRAME_INTERVAL_MS: i32 = 33;
+
+    struct Options {
+        width: u8,
+        height: u8,
+        link: Option<PathBuf>,
+        render: bool,
+    }
+
+    fn parse_args() -> Result<Options, String> {
+        let mut options = Options {
+            width: 64,
+            height: 32,
+            link: None,
+            render: false,
+        };
+        let mut args = env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--size" => {
+                    let size = args.next().ok_or("--size needs a value")?;
+                    let (width, height) = size
+                        .split_once('x')
+                        .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+                        .ok_or_else(|| format!("invalid size '{}', expected e.g. 64x32", size))?;
+                    options.width = width;
+                    options.height = height;
+                }
+                "--link" => options.link = Some(args.next().ok_or("--link needs a path")?.into()),
+                "--render" => options.render = true,
+                _ => return Err(format!("unknown argument '{}'", arg)),
+            }
+        }
+
+        Ok(options)
+    }
+
+    pub fn run() -> Result<(), Box<dyn std::error::Error>> {
+        let options = parse_args()?;
+        let pty = openpty(None, None)?;
+
+        //Raw mode, so that bytes such as 0x0D or 0x03 reach the emulator unchanged
+        let mut termios = tcgetattr(pty.slave)?;
+        cfmakeraw(&mut termios);
+        tcsetattr(pty.slave, SetArg::TCSANOW, &termios)?;
+
+        //Keeping our own handle on the slave open means the master does not report
+        //EIO every time a client disconnects
+        let _slave = unsafe { File::from_raw_fd(pty.slave) };
+        let mut master = unsafe { File::from_raw_fd(pty.master) };
+        let path = ttyname(pty.slave)?;
+
+        let location = match &options.link {
+            Some(link) => {
+                let _ = fs::remove_file(link);
+                std::os::unix::fs::symlink(&path, link)?;
+                format!("{} -> {}", link.display(), path.display())
+            }
+            None => path.display().to_string(),
+        };
+        println!("{}", location);
+        println!(
+            "Emulating a {}x{} matrix, add the path to UMX_EXTRA_PORTS to list it in the app",
+            options.width, options.height
+        );
+
+        let mut emulator = Emulator::new(options.width, options.height);
+        let start = Instant::now();
+        let mut last_nack = String::new();
+        let mut dirty = options.render;
+        if options.render {
+            print!("\x1b[2J");
+        }
+
+        let mut buffer = [0; 256];
+        loop {
+            let animated = options.render && emulator.state().is_animated();
+            if dirty || animated {
+                let image = emulator.state().render(start.elapsed());
+                print!(
+                    "{}\x1b[0K{}\n\x1b[0K{}",
+                    terminal::to_ansi(&image),
+                    location,
+                    last_nack
+                );
+                io::stdout().flush()?;
+                dirty = false;
+            }
+
+            //Without animations there is nothing to redraw until the host sends something
+            let timeout = if animated { FRAME_INTERVAL_MS } else { -1 };
+            let mut fds = [PollFd::new(pty.master, PollFlags::POLLIN)];
+            match poll(&mut fds, timeout) {
+                Ok(0) | Err(Errno::EINTR) => continue,
+                Ok(_) => {}
+                Err(e) => return Err(e.into()),
+            }
+
+            let count = match master.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(count) => count,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            };
+
+            let reply = emulator.feed(&buffer[..count]);
+            for nack in nacks(&reply) {
+                if options.render {
+                    last_nack = nack;
+                } else {
+                    eprintln!("{}", nack);
+                }
+            }
+            master.write_all(&reply)?;
+            dirty = options.render;
+        }
+
+        if let Some(link) = &options.link {
+            let _ = fs::remove_file(link);
+        }
+        Ok(())
+    }
+
+    fn nacks(reply: &[u8]) -> Vec<String> {
+        let mut nacks = vec![];
+        FrameDecoder::new().feed(reply, |payload| {
+            if let Ok(Response::Nack { opcode, reason }) = Response::decode(payload) {
+                nacks.push(format!("NACK for opcode {}: {:?}", opcode, reason));
+            }
+        });
+        nacks
+    }
+}
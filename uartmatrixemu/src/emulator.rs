@@ -0,0 +1,388 @@
+use std::time::Duration;
+
+use image::{Rgb, RgbImage};
+use libuartmatrix::enums::{Animation, Direction, DisplayMode, FontType};
+use libuartmatrix::raster::RasterTarget;
+use libuartmatrix::{DeviceParams, NackReason, Point, RgbColor};
+use uartmatrixdevice::{MatrixBackend, ProtocolEngine};
+
+/// Text, font, color and animation of one text-mode row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextRow {
+    pub text: String,
+    pub font: FontType,
+    pub color: RgbColor,
+    pub animation: Animation,
+}
+
+impl Default for TextRow {
+    fn default() -> Self {
+        TextRow {
+            text: String::new(),
+            font: FontType::Default,
+            color: RgbColor::new(),
+            animation: Animation::NoAnimation,
+        }
+    }
+}
+
+/// Everything a real panel would keep in memory, updated by the protocol engine.
+#[derive(Debug, Clone)]
+pub struct MatrixState {
+    params: DeviceParams,
+    mode: DisplayMode,
+    output_enabled: bool,
+    framebuffer: RgbImage,
+    rows: Vec<TextRow>,
+}
+
+impl MatrixState {
+    pub fn new(params: DeviceParams) -> Self {
+        MatrixState {
+            params,
+            mode: DisplayMode::Text,
+            output_enabled: true,
+            framebuffer: RgbImage::new(params.width as u32, params.height as u32),
+            rows: vec![TextRow::default(); params.text_rows as usize],
+        }
+    }
+
+    pub fn mode(&self) -> DisplayMode {
+        self.mode
+    }
+
+    pub fn output_enabled(&self) -> bool {
+        self.output_enabled
+    }
+
+    pub fn framebuffer(&self) -> &RgbImage {
+        &self.framebuffer
+    }
+
+    pub fn rows(&self) -> &[TextRow] {
+        &self.rows
+    }
+
+    /// True when the displayed image changes over time even without new commands.
+    pub fn is_animated(&self) -> bool {
+        self.output_enabled
+            && self.mode == DisplayMode::Text
+            && self
+                .rows
+                .iter()
+                .any(|row| !row.text.is_empty() && row.animation != Animation::NoAnimation)
+    }
+
+    /// What the panel shows `elapsed` after start-up.
+    ///
+    /// Direct mode shows the framebuffer. Text mode splits the panel into equally
+    /// high rows and draws each one with its color and animation; blink speeds are
+    /// in Hz and slide speeds in pixels per second.
+    pub fn render(&self, elapsed: Duration) -> RgbImage {
+        let (width, height) = self.framebuffer.dimensions();
+        if !self.output_enabled {
+            return RgbImage::new(width, height);
+        }
+        if self.mode == DisplayMode::Direct {
+            return self.framebuffer.clone();
+        }
+
+        let mut image = RgbImage::new(width, height);
+        let row_height = height / self.rows.len().max(1) as u32;
+        for (i, row) in self.rows.iter().enumerate() {
+            let font = row.font.bitmap();
+            let y = i as u32 * row_height + row_height.saturating_sub(font.height()) / 2;
+            let x = match row.animation {
+                Animation::NoAnimation => 0,
+                Animation::BlinkAnimation(speed) => {
+                    let half_periods = elapsed.as_secs_f64() * speed as f64 * 2.0;
+                    if half_periods as u64 % 2 == 1 {
+                        continue;
+                    }
+                    0
+                }
+                Animation::SlideAnimation(speed, direction) => {
+                    //The text enters on one side and has fully left the other before it wraps
+                    let distance = font.text_width(&row.text) + width;
+                    let offset = (elapsed.as_secs_f64() * speed as f64) as u64 % distance as u64;
+                    match direction {
+                        Direction::Left => width as i32 - offset as i32,
+                        Direction::Right => offset as i32 - font.text_width(&row.text) as i32,
+                    }
+                }
+            };
+
+            font.render(
+                &mut ImageTarget(&mut image),
+                x,
+                y as i32,
+                &row.text,
+                row.color,
+            );
+        }
+
+        image
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> RgbColor {
+        let Rgb([r, g, b]) = *self.framebuffer.get_pixel(x, y);
+        RgbColor::from_rgb(r, g, b)
+    }
+}
+
+fn to_rgb(color: RgbColor) -> Rgb<u8> {
+    Rgb([color.r, color.g, color.b])
+}
+
+struct ImageTarget<'a>(&'a mut RgbImage);
+
+impl RasterTarget for ImageTarget<'_> {
+    fn width(&self) -> u32 {
+        self.0.width()
+    }
+
+    fn height(&self) -> u32 {
+        self.0.height()
+    }
+
+    fn set_pixel(&mut self, x: u32, y: u32, color: RgbColor) {
+        self.0.put_pixel(x, y, to_rgb(color));
+    }
+}
+
+impl MatrixBackend for MatrixState {
+    fn params(&self) -> DeviceParams {
+        self.params
+    }
+
+    fn switch_mode(&mut self, mode: DisplayMode) -> Result<(), NackReason> {
+        self.mode = mode;
+        Ok(())
+    }
+
+    fn write_line(&mut self, row: u8, text: &str) -> Result<(), NackReason> {
+        self.rows[row as usize].text = String::from(text);
+        Ok(())
+    }
+
+    fn set_font(&mut self, row: u8, font: FontType) -> Result<(), NackReason> {
+        self.rows[row as usize].font = font;
+        Ok(())
+    }
+
+    fn set_color(&mut self, row: u8, color: RgbColor) -> Result<(), NackReason> {
+        self.rows[row as usize].color = color;
+        Ok(())
+    }
+
+    fn set_animation(&mut self, row: u8, animation: Animation) -> Result<(), NackReason> {
+        self.rows[row as usize].animation = animation;
+        Ok(())
+    }
+
+    fn set_pixel(&mut self, position: Point, color: RgbColor) -> Result<(), NackReason> {
+        //The engine only hands over coordinates inside the panel
+        self.framebuffer
+            .put_pixel(position.x as u32, position.y as u32, to_rgb(color));
+        Ok(())
+    }
+
+    fn draw_row(&mut self, row: u8, pixels: &[RgbColor]) -> Result<(), NackReason> {
+        for (x, color) in pixels.iter().enumerate() {
+            self.framebuffer
+                .put_pixel(x as u32, row as u32, to_rgb(*color));
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self) -> Result<(), NackReason> {
+        self.framebuffer = RgbImage::new(self.framebuffer.width(), self.framebuffer.height());
+        for row in self.rows.iter_mut() {
+            row.text.clear();
+        }
+        Ok(())
+    }
+
+    fn set_output_enabled(&mut self, enabled: bool) -> Result<(), NackReason> {
+        self.output_enabled = enabled;
+        Ok(())
+    }
+}
+
+/// A matrix panel in memory: feed it UMX frames and inspect the result.
+pub struct Emulator {
+    engine: ProtocolEngine<MatrixState>,
+}
+
+impl Emulator {
+    /// Emulates a panel of the given size with the default text rows and fonts.
+    pub fn new(width: u8, height: u8) -> Self {
+        Emulator::with_params(DeviceParams {
+            width,
+            height,
+            ..DeviceParams::default()
+        })
+    }
+
+    pub fn with_params(params: DeviceParams) -> Self {
+        Emulator {
+            engine: ProtocolEngine::new(MatrixState::new(params)),
+        }
+    }
+
+    /// Feeds bytes sent by the host and returns the response frames the panel sends back.
+    pub fn feed(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut responses = vec![];
+        self.engine
+            .feed(data, |frame| responses.extend_from_slice(frame));
+        responses
+    }
+
+    pub fn state(&self) -> &MatrixState {
+        self.engine.backend()
+    }
+
+    pub fn framebuffer(&self) -> &RgbImage {
+        self.state().framebuffer()
+    }
+}
+
+impl Default for Emulator {
+    fn default() -> Self {
+        Emulator::with_params(DeviceParams::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libuartmatrix::enums::Opcode;
+    use libuartmatrix::*;
+
+    fn responses(data: &[u8]) -> Vec<Response> {
+        let mut responses = vec![];
+        FrameDecoder::new().feed(data, |payload| {
+            responses.push(Response::decode(payload).unwrap())
+        });
+        responses
+    }
+
+    #[test]
+    fn applies_text_mode_commands() {
+        let mut emulator = Emulator::default();
+        let red = RgbColor::from_rgb(255, 0, 0);
+        let frames = [
+            serialize_write_line(1, "HELLO").unwrap(),
+            serialize_set_font(1, FontType::Ibm.into()).unwrap(),
+            serialize_set_color(1, red).unwrap(),
+            serialize_set_animation(1, Animation::BlinkAnimation(3)).unwrap(),
+        ];
+
+        for frame in frames.iter() {
+            emulator.feed(frame);
+        }
+
+        assert_eq!(
+            emulator.state().rows()[1],
+            TextRow {
+                text: String::from("HELLO"),
+                font: FontType::Ibm,
+                color: red,
+                animation: Animation::BlinkAnimation(3),
+            }
+        );
+        assert_eq!(emulator.state().rows()[0], TextRow::default());
+    }
+
+    #[test]
+    fn draws_into_framebuffer() {
+        let mut emulator = Emulator::new(16, 8);
+        let green = RgbColor::from_rgb(0, 255, 0);
+        let blue = RgbColor::from_rgb(0, 0, 255);
+
+        emulator.feed(&serialize_draw_row(2, vec![(1, 2, 3); 16]).unwrap());
+        emulator.feed(&serialize_draw_pixel(Point { x: 15, y: 7 }, green).unwrap());
+        let reply = emulator.feed(
+            &serialize_draw_rectangle(Point { x: 0, y: 4 }, Point { x: 3, y: 7 }, 1, blue, true)
+                .unwrap(),
+        );
+
+        assert_eq!(
+            responses(&reply),
+            vec![Response::Ack {
+                opcode: Opcode::DrawRectangle.into()
+            }]
+        );
+        assert_eq!(emulator.framebuffer().dimensions(), (16, 8));
+        assert_eq!(emulator.state().pixel(9, 2), RgbColor::from_rgb(1, 2, 3));
+        assert_eq!(emulator.state().pixel(15, 7), green);
+        assert_eq!(emulator.state().pixel(2, 5), blue);
+        assert_eq!(emulator.state().pixel(4, 5), RgbColor::from_rgb(0, 0, 0));
+
+        emulator.feed(&serialize_clear().unwrap());
+        assert!(emulator
+            .framebuffer()
+            .pixels()
+            .all(|pixel| pixel.0 == [0, 0, 0]));
+    }
+
+    #[test]
+    fn clips_shapes_at_the_edge() {
+        let mut emulator = Emulator::new(8, 8);
+        let white = RgbColor::from_rgb(255, 255, 255);
+
+        emulator.feed(&serialize_draw_circle(Point { x: 7, y: 7 }, 5, 1, white, false).unwrap());
+        emulator.feed(
+            &serialize_draw_line(Point { x: 0, y: 0 }, Point { x: 200, y: 0 }, 3, white).unwrap(),
+        );
+
+        assert_eq!(emulator.state().pixel(7, 2), white);
+        assert_eq!(emulator.state().pixel(7, 7), RgbColor::from_rgb(0, 0, 0));
+        assert_eq!(emulator.state().pixel(5, 1), white);
+    }
+
+    #[test]
+    fn renders_text_rows_with_animations() {
+        let mut emulator = Emulator::default();
+        let white = RgbColor::from_rgb(255, 255, 255);
+        emulator.feed(&serialize_write_line(0, "|").unwrap());
+        emulator.feed(&serialize_set_color(0, white).unwrap());
+        emulator.feed(&serialize_set_animation(0, Animation::BlinkAnimation(1)).unwrap());
+
+        //'|' is a single line in the middle column of the glyph
+        let lit = |image: &RgbImage| image.pixels().any(|pixel| pixel.0 == [255, 255, 255]);
+        assert!(emulator.state().is_animated());
+        assert!(lit(&emulator.state().render(Duration::from_millis(100))));
+        assert!(!lit(&emulator.state().render(Duration::from_millis(600))));
+
+        emulator.feed(
+            &serialize_set_animation(0, Animation::SlideAnimation(10, Direction::Left)).unwrap(),
+        );
+        let image = emulator.state().render(Duration::from_secs(1));
+        let columns: Vec<u32> = image
+            .enumerate_pixels()
+            .filter(|(_, _, pixel)| pixel.0 == [255, 255, 255])
+            .map(|(x, _, _)| x)
+            .collect();
+        assert!(!columns.is_empty());
+        assert!(columns.iter().all(|x| *x == 56));
+
+        emulator.feed(&serialize_switch_mode(DisplayMode::Direct).unwrap());
+        assert!(!emulator.state().is_animated());
+        assert!(!lit(&emulator.state().render(Duration::from_secs(1))));
+    }
+
+    #[test]
+    fn rejects_out_of_range_rows() {
+        let mut emulator = Emulator::default();
+        let reply = emulator.feed(&serialize_write_line(3, "NOPE").unwrap());
+
+        assert_eq!(
+            responses(&reply),
+            vec![Response::Nack {
+                opcode: Opcode::WriteLine.into(),
+                reason: NackReason::BadRow
+            }]
+        );
+    }
+}
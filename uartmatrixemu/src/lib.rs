@@ -0,0 +1,14 @@
+//! Headless UMX panel for testing without hardware.
+//!
+//! The [`Emulator`] runs the device-side protocol engine on top of an in-memory
+//! framebuffer and text-row state, so tests can send real frames and then assert on
+//! the resulting pixels. [`Loopback`] puts it behind a transport for the host-side
+//! client. [`terminal::to_ansi`] turns the displayed image into text for watching the
+//! panel in a terminal.
+
+pub use emulator::{Emulator, MatrixState, TextRow};
+pub use loopback::Loopback;
+
+mod emulator;
+mod loopback;
+pub mod terminal;
@@ -0,0 +1,87 @@
+use std::io;
+use std::thread;
+use std::time::Duration;
+
+use libuartmatrix::Transport;
+
+use crate::Emulator;
+
+/// In-memory transport to an [`Emulator`], for driving a client without hardware.
+///
+/// Sent frames are handled right away, so the replies are waiting by the time the client
+/// reads them.
+pub struct Loopback {
+    emulator: Emulator,
+    replies: Vec<u8>,
+}
+
+impl Loopback {
+    pub fn new(emulator: Emulator) -> Self {
+        Loopback {
+            emulator,
+            replies: vec![],
+        }
+    }
+
+    pub fn emulator(&self) -> &Emulator {
+        &self.emulator
+    }
+
+    pub fn emulator_mut(&mut self) -> &mut Emulator {
+        &mut self.emulator
+    }
+
+    pub fn into_emulator(self) -> Emulator {
+        self.emulator
+    }
+}
+
+impl Transport for Loopback {
+    fn send(&mut self, data: &[u8]) -> io::Result<()> {
+        let replies = self.emulator.feed(data);
+        self.replies.extend_from_slice(&replies);
+        Ok(())
+    }
+
+    fn receive(&mut self, buffer: &mut [u8], timeout: Duration) -> io::Result<usize> {
+        //Nothing else could produce a reply while waiting
+        if self.replies.is_empty() {
+            thread::sleep(timeout);
+            return Ok(0);
+        }
+
+        let count = self.replies.len().min(buffer.len());
+        buffer[..count].clone_from_slice(&self.replies[..count]);
+        self.replies.drain(..count);
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libuartmatrix::enums::DisplayMode;
+    use libuartmatrix::*;
+
+    #[test]
+    fn client_drives_emulator() {
+        let mut client = MatrixClient::new(Loopback::new(Emulator::new(16, 8)));
+        let red = RgbColor::from_rgb(255, 0, 0);
+
+        assert_eq!(client.ping().unwrap(), PROTOCOL_VERSION);
+        assert_eq!(client.params().unwrap().width, 16);
+
+        client.switch_mode(DisplayMode::Direct).unwrap();
+        client.draw_pixel(Point { x: 3, y: 2 }, red).unwrap();
+        client.write_line(0, "HI").unwrap();
+        assert_eq!(client.ack_mode(), AckMode::Acknowledged);
+        assert!(matches!(
+            client.write_line(9, "HI"),
+            Err(ClientError::Rejected(NackReason::BadRow))
+        ));
+
+        let emulator = client.into_transport().into_emulator();
+        assert_eq!(emulator.state().pixel(3, 2), red);
+        assert_eq!(emulator.state().rows()[0].text, "HI");
+    }
+}
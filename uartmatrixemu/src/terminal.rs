@@ -0,0 +1,53 @@
+use std::fmt::Write;
+
+use image::RgbImage;
+
+/// Renders the image as ANSI truecolor text, two pixel rows per line of "▀".
+///
+/// The upper pixel is the foreground and the lower one the background color of each
+/// half-block. The output starts by moving the cursor home, so printing it repeatedly
+/// redraws in place.
+pub fn to_ansi(image: &RgbImage) -> String {
+    let mut output = String::from("\x1b[H");
+
+    for y in (0..image.height()).step_by(2) {
+        for x in 0..image.width() {
+            let top = image.get_pixel(x, y).0;
+            let bottom = if y + 1 < image.height() {
+                image.get_pixel(x, y + 1).0
+            } else {
+                [0, 0, 0]
+            };
+
+            let _ = write!(
+                output,
+                "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m\u{2580}",
+                top[0], top[1], top[2], bottom[0], bottom[1], bottom[2]
+            );
+        }
+        output.push_str("\x1b[0m\n");
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    #[test]
+    fn pairs_rows_into_half_blocks() {
+        let mut image = RgbImage::new(2, 3);
+        image.put_pixel(0, 0, Rgb([255, 0, 0]));
+        image.put_pixel(0, 1, Rgb([0, 0, 255]));
+        image.put_pixel(1, 2, Rgb([0, 255, 0]));
+
+        let output = to_ansi(&image);
+        let lines: Vec<&str> = output.trim_start_matches("\x1b[H").lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("\x1b[38;2;255;0;0m\x1b[48;2;0;0;255m\u{2580}"));
+        assert!(lines[1].ends_with("\x1b[38;2;0;255;0m\x1b[48;2;0;0;0m\u{2580}\x1b[0m"));
+    }
+}
@@ -0,0 +1,393 @@
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use libuartmatrix::enums::{Animation, Direction, DisplayMode, FontType};
+use libuartmatrix::*;
+
+pub const USAGE: &str = "\
+Usage: umxctl [--port PATH] [--baud RATE] [--tcp HOST:PORT] COMMAND [ARGS]
+
+Connection:
+  --port PATH       serial port of the panel, defaults to $UMX_PORT
+  --baud RATE       baud rate of the serial port, defaults to 115200
+  --tcp HOST:PORT   talk to a serial bridge over TCP instead
+
+Commands:
+  text --row N TEXT...                        write a line of text
+  color --row N RRGGBB                        set the text color of a row
+  anim --row N none|blink|slide [--speed N] [--dir left|right]
+                                              animate a row, speed in Hz or px/s (max 60)
+  font --row N default|pro|ibm                set the font of a row
+  mode text|direct                            switch the display mode
+  pixel X Y RRGGBB                            draw a pixel
+  line X1 Y1 X2 Y2 RRGGBB [--thickness N]     draw a line
+  rect X1 Y1 X2 Y2 RRGGBB [--thickness N] [--filled]
+  tri X1 Y1 X2 Y2 X3 Y3 RRGGBB [--thickness N] [--filled]
+  circle X Y RADIUS RRGGBB [--thickness N] [--filled]
+  image FILE                                  draw a PNG or JPEG scaled to the panel
+  clear                                       clear the panel
+  ping                                        check that the panel answers
+  info                                        print the parameters of the panel
+  list-ports                                  list the available serial ports
+";
+
+/// How to reach the panel, before falling back to the environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Connection {
+    pub port: Option<String>,
+    pub baud: u32,
+    pub tcp: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum Action {
+    /// A command that only needs to be delivered.
+    Send(Box<Frame>),
+    Image(PathBuf),
+    Ping,
+    Info,
+    ListPorts,
+    Help,
+}
+
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<(Connection, Action), String> {
+    let mut args: VecDeque<String> = args.into_iter().collect();
+    let mut connection = Connection {
+        port: None,
+        baud: 115200,
+        tcp: None,
+    };
+
+    let command = loop {
+        let arg = match args.pop_front() {
+            Some(arg) => arg,
+            None => return Ok((connection, Action::Help)),
+        };
+
+        match arg.as_str() {
+            "--port" => connection.port = Some(args.pop_front().ok_or("--port needs a path")?),
+            "--baud" => {
+                let baud = args.pop_front().ok_or("--baud needs a rate")?;
+                connection.baud = parse_value("baud rate", &baud)?;
+            }
+            "--tcp" => connection.tcp = Some(args.pop_front().ok_or("--tcp needs an address")?),
+            "-h" | "--help" | "help" => return Ok((connection, Action::Help)),
+            _ if arg.starts_with("--") => return Err(format!("unknown option '{}'", arg)),
+            _ => break arg,
+        }
+    };
+
+    let action = parse_command(&command, Arguments::split(args)?)?;
+    Ok((connection, action))
+}
+
+fn parse_command(command: &str, mut args: Arguments) -> Result<Action, String> {
+    let action = match command {
+        "text" => {
+            let row = args.required("row")?;
+            let text = args.rest("text")?.join(" ");
+            Action::Send(encode(serialize_write_line(row, &text))?)
+        }
+        "color" => {
+            let row = args.required("row")?;
+            let color = parse_color(&args.positional("color")?)?;
+            Action::Send(encode(serialize_set_color(row, color))?)
+        }
+        "anim" => {
+            let row = args.required("row")?;
+            let animation = match args.positional("animation")?.as_str() {
+                "none" => Animation::NoAnimation,
+                "blink" => Animation::BlinkAnimation(frames_per_step(args.required("speed")?)?),
+                "slide" => {
+                    let speed = frames_per_step(args.required("speed")?)?;
+                    let direction = match args.option("dir")?.as_deref() {
+                        None | Some("left") => Direction::Left,
+                        Some("right") => Direction::Right,
+                        Some(other) => return Err(format!("unknown direction '{}'", other)),
+                    };
+                    Animation::SlideAnimation(speed, direction)
+                }
+                other => return Err(format!("unknown animation '{}'", other)),
+            };
+            Action::Send(encode(serialize_set_animation(row, animation))?)
+        }
+        "font" => {
+            let row = args.required("row")?;
+            let font = match args.positional("font")?.as_str() {
+                "default" => FontType::Default,
+                "pro" => FontType::Pro,
+                "ibm" => FontType::Ibm,
+                other => return Err(format!("unknown font '{}'", other)),
+            };
+            Action::Send(encode(serialize_set_font(row, font.into()))?)
+        }
+        "mode" => {
+            let mode = match args.positional("mode")?.as_str() {
+                "text" => DisplayMode::Text,
+                "direct" => DisplayMode::Direct,
+                other => return Err(format!("unknown mode '{}'", other)),
+            };
+            Action::Send(encode(serialize_switch_mode(mode))?)
+        }
+        "pixel" => {
+            let position = args.point()?;
+            let color = parse_color(&args.positional("color")?)?;
+            Action::Send(encode(serialize_draw_pixel(position, color))?)
+        }
+        "line" => {
+            let (point_1, point_2) = (args.point()?, args.point()?);
+            let color = parse_color(&args.positional("color")?)?;
+            let thickness = args.thickness()?;
+            Action::Send(encode(serialize_draw_line(
+                point_1, point_2, thickness, color,
+            ))?)
+        }
+        "rect" => {
+            let (point_1, point_2) = (args.point()?, args.point()?);
+            let color = parse_color(&args.positional("color")?)?;
+            let thickness = args.thickness()?;
+            let filled = args.flag("filled");
+            Action::Send(encode(serialize_draw_rectangle(
+                point_1, point_2, thickness, color, filled,
+            ))?)
+        }
+        "tri" => {
+            let (point_1, point_2, point_3) = (args.point()?, args.point()?, args.point()?);
+            let color = parse_color(&args.positional("color")?)?;
+            let thickness = args.thickness()?;
+            let filled = args.flag("filled");
+            Action::Send(encode(serialize_draw_triangle(
+                point_1, point_2, point_3, thickness, color, filled,
+            ))?)
+        }
+        "circle" => {
+            let center = args.point()?;
+            let radius = parse_value("radius", &args.positional("radius")?)?;
+            let color = parse_color(&args.positional("color")?)?;
+            let thickness = args.thickness()?;
+            let filled = args.flag("filled");
+            Action::Send(encode(serialize_draw_circle(
+                center, radius, thickness, color, filled,
+            ))?)
+        }
+        "image" => Action::Image(args.positional("file")?.into()),
+        "clear" => Action::Send(encode(serialize_clear())?),
+        "ping" => Action::Ping,
+        "info" => Action::Info,
+        "list-ports" => Action::ListPorts,
+        _ => return Err(format!("unknown command '{}'", command)),
+    };
+
+    args.finish()?;
+    Ok(action)
+}
+
+fn encode(frame: Result<Frame, UmxError>) -> Result<Box<Frame>, String> {
+    frame.map(Box::new).map_err(|e| e.to_string())
+}
+
+fn parse_value<T: FromStr>(what: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid {} '{}'", what, value))
+}
+
+/// Parses `RRGGBB`, optionally prefixed with `#`.
+fn parse_color(value: &str) -> Result<RgbColor, String> {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+
+    match (hex.len(), channel(0), channel(2), channel(4)) {
+        (6, Some(r), Some(g), Some(b)) => Ok(RgbColor::from_rgb(r, g, b)),
+        _ => Err(format!("invalid color '{}', expected e.g. ff8800", value)),
+    }
+}
+
+/// Converts a speed in Hz or px/s into display frames per step, the same way the app does.
+fn frames_per_step(speed: u8) -> Result<u8, String> {
+    match 60u8.checked_div(speed) {
+        Some(frames) if frames > 0 => Ok(frames),
+        _ => Err(format!("invalid speed {}, expected 1 to 60", speed)),
+    }
+}
+
+/// The arguments after the command, split into `--name value` options, flags and the rest.
+struct Arguments {
+    positional: VecDeque<String>,
+    options: Vec<(String, String)>,
+    flags: Vec<String>,
+}
+
+impl Arguments {
+    const FLAGS: [&'static str; 1] = ["filled"];
+
+    fn split(mut args: VecDeque<String>) -> Result<Self, String> {
+        let mut arguments = Arguments {
+            positional: VecDeque::new(),
+            options: vec![],
+            flags: vec![],
+        };
+
+        while let Some(arg) = args.pop_front() {
+            match arg.strip_prefix("--") {
+                Some(name) if Self::FLAGS.contains(&name) => arguments.flags.push(name.into()),
+                Some(name) => {
+                    let value = args
+                        .pop_front()
+                        .ok_or_else(|| format!("{} needs a value", arg))?;
+                    arguments.options.push((name.into(), value));
+                }
+                None => arguments.positional.push_back(arg),
+            }
+        }
+
+        Ok(arguments)
+    }
+
+    fn option(&mut self, name: &str) -> Result<Option<String>, String> {
+        let index = match self.options.iter().position(|(option, _)| option == name) {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        let (_, value) = self.options.remove(index);
+
+        if self.options.iter().any(|(option, _)| option == name) {
+            return Err(format!("--{} given more than once", name));
+        }
+        Ok(Some(value))
+    }
+
+    fn required<T: FromStr>(&mut self, name: &str) -> Result<T, String> {
+        let value = self
+            .option(name)?
+            .ok_or_else(|| format!("missing --{}", name))?;
+        parse_value(name, &value)
+    }
+
+    fn thickness(&mut self) -> Result<u8, String> {
+        match self.option("thickness")? {
+            Some(value) => parse_value("thickness", &value),
+            None => Ok(1),
+        }
+    }
+
+    fn flag(&mut self, name: &str) -> bool {
+        let count = self.flags.len();
+        self.flags.retain(|flag| flag != name);
+        self.flags.len() != count
+    }
+
+    fn positional(&mut self, what: &str) -> Result<String, String> {
+        self.positional
+            .pop_front()
+            .ok_or_else(|| format!("missing {}", what))
+    }
+
+    fn rest(&mut self, what: &str) -> Result<Vec<String>, String> {
+        if self.positional.is_empty() {
+            return Err(format!("missing {}", what));
+        }
+        Ok(self.positional.drain(..).collect())
+    }
+
+    fn point(&mut self) -> Result<Point, String> {
+        let x = parse_value("x coordinate", &self.positional("x coordinate")?)?;
+        let y = parse_value("y coordinate", &self.positional("y coordinate")?)?;
+        Ok(Point { x, y })
+    }
+
+    /// Fails on anything the command did not use.
+    fn finish(self) -> Result<(), String> {
+        if let Some(arg) = self.positional.front() {
+            return Err(format!("unexpected argument '{}'", arg));
+        }
+        if let Some((name, _)) = self.options.first() {
+            return Err(format!("unknown option '--{}'", name));
+        }
+        if let Some(name) = self.flags.first() {
+            return Err(format!("unknown option '--{}'", name));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &str) -> Result<(Connection, Action), String> {
+        parse(args.split_whitespace().map(String::from))
+    }
+
+    fn sent(args: &str) -> Frame {
+        match parse_args(args).unwrap().1 {
+            Action::Send(frame) => *frame,
+            action => panic!("expected a frame, got {:?}", action),
+        }
+    }
+
+    #[test]
+    fn commands_use_the_serializers() {
+        assert_eq!(
+            sent("text --row 0 Hello world").as_bytes(),
+            serialize_write_line(0, "Hello world").unwrap().as_bytes()
+        );
+        assert_eq!(
+            sent("color --row 1 #ff8800").as_bytes(),
+            serialize_set_color(1, RgbColor::from_rgb(0xff, 0x88, 0x00))
+                .unwrap()
+                .as_bytes()
+        );
+        assert_eq!(
+            sent("anim --row 0 slide --speed 20 --dir right").as_bytes(),
+            serialize_set_animation(0, Animation::SlideAnimation(3, Direction::Right))
+                .unwrap()
+                .as_bytes()
+        );
+        assert_eq!(
+            sent("rect 1 2 10 12 00ff00 --filled --thickness 2").as_bytes(),
+            serialize_draw_rectangle(
+                Point { x: 1, y: 2 },
+                Point { x: 10, y: 12 },
+                2,
+                RgbColor::from_rgb(0, 0xff, 0),
+                true
+            )
+            .unwrap()
+            .as_bytes()
+        );
+    }
+
+    #[test]
+    fn connection_options_come_before_the_command() {
+        let (connection, action) = parse_args("--port /dev/ttyUSB1 --baud 230400 ping").unwrap();
+
+        assert_eq!(connection.port.as_deref(), Some("/dev/ttyUSB1"));
+        assert_eq!(connection.baud, 230400);
+        assert!(matches!(action, Action::Ping));
+        assert!(matches!(parse_args("").unwrap().1, Action::Help));
+    }
+
+    #[test]
+    fn invalid_arguments_are_rejected() {
+        assert_eq!(
+            parse_args("color --row 1 ff88").unwrap_err(),
+            "invalid color 'ff88', expected e.g. ff8800"
+        );
+        assert_eq!(parse_args("text Hello").unwrap_err(), "missing --row");
+        assert_eq!(
+            parse_args("anim --row 0 blink --speed 0").unwrap_err(),
+            "invalid speed 0, expected 1 to 60"
+        );
+        assert_eq!(
+            parse_args("clear now").unwrap_err(),
+            "unexpected argument 'now'"
+        );
+        assert_eq!(
+            parse_args("pixel 1 2 ffffff --filled").unwrap_err(),
+            "unknown option '--filled'"
+        );
+        assert_eq!(parse_args("blink").unwrap_err(), "unknown command 'blink'");
+    }
+}
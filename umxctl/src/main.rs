@@ -0,0 +1,116 @@
+//! Drives a matrix from the command line, e.g. from shell scripts or cron.
+//!
+//! Usage: `umxctl [--port PATH] [--baud RATE] [--tcp HOST:PORT] COMMAND [ARGS]`
+//!
+//! Run `umxctl help` for the list of commands. Every command opens the connection, sends
+//! its frames and waits for the panel to acknowledge them, so a non-zero exit status means
+//! the panel did not take the command.
+
+mod args;
+
+use std::env;
+use std::error::Error;
+use std::net::TcpStream;
+use std::process;
+
+use image::imageops::FilterType;
+use libuartmatrix::{ClientError, DeviceParams, MatrixClient, Transport};
+
+use crate::args::{Action, Connection, USAGE};
+
+type Client = MatrixClient<Box<dyn Transport>>;
+
+fn main() {
+    let (connection, action) = match args::parse(env::args().skip(1)) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("umxctl: {}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+
+    if let Err(e) = run(connection, action) {
+        eprintln!("umxctl: {}", e);
+        process::exit(1);
+    }
+}
+
+fn run(connection: Connection, action: Action) -> Result<(), Box<dyn Error>> {
+    match action {
+        Action::Help => print!("{}", USAGE),
+        Action::ListPorts => {
+            for port in serialport::available_ports()? {
+                println!("{}", port.port_name);
+            }
+            //Pseudo-terminals such as the one served by umx-pty are not enumerated by the OS
+            if let Some(extra_ports) = env::var_os("UMX_EXTRA_PORTS") {
+                for path in env::split_paths(&extra_ports) {
+                    println!("{}", path.display());
+                }
+            }
+        }
+        Action::Ping => {
+            let version = connect(&connection)?.ping()?;
+            println!("Pong, protocol version {}", version);
+        }
+        Action::Info => {
+            let params = connect(&connection)?.params()?;
+            println!("Protocol version: {}", params.protocol_version);
+            println!("Size: {}x{}", params.width, params.height);
+            println!("Text rows: {}", params.text_rows);
+            let fonts: Vec<String> = params.fonts().map(|font| format!("{:?}", font)).collect();
+            println!("Fonts: {}", fonts.join(", "));
+            let (major, minor, patch) = params.firmware_version;
+            println!("Firmware: {}.{}.{}", major, minor, patch);
+            println!("Max frame size: {}", params.max_frame_size);
+        }
+        Action::Send(frame) => {
+            connect(&connection)?.send_frame(&frame)?;
+        }
+        Action::Image(path) => {
+            let mut client = connect(&connection)?;
+            let params = match client.params() {
+                Ok(params) => params,
+                Err(ClientError::Io(e)) => return Err(e.into()),
+                //Firmware without parameter reports drives the default panel
+                Err(_) => DeviceParams::default(),
+            };
+
+            let image = image::open(&path)
+                .map_err(|e| format!("{}: {}", path.display(), e))?
+                .resize(
+                    params.width as u32,
+                    params.height as u32,
+                    FilterType::Lanczos3,
+                )
+                .into_rgb8();
+            client.upload_image(
+                image
+                    .rows()
+                    .map(|row| row.map(|pixel| (pixel[0], pixel[1], pixel[2]))),
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn connect(connection: &Connection) -> Result<Client, Box<dyn Error>> {
+    let transport: Box<dyn Transport> = match &connection.tcp {
+        Some(address) => Box::new(TcpStream::connect(address)?),
+        None => {
+            let port = match &connection.port {
+                Some(port) => port.clone(),
+                None => {
+                    env::var("UMX_PORT").map_err(|_| "no port given, use --port or UMX_PORT")?
+                }
+            };
+            let serial = serialport::new(&port, connection.baud)
+                .open()
+                .map_err(|e| format!("{}: {}", port, e))?;
+            Box::new(serial)
+        }
+    };
+
+    Ok(MatrixClient::new(transport))
+}